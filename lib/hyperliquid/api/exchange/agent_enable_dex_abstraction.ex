@@ -34,21 +34,25 @@ defmodule Hyperliquid.Api.Exchange.AgentEnableDexAbstraction do
       enabled: enabled
     }
 
-    with {:ok, action_json} <- Jason.encode(action),
-         {:ok, signature} <- sign_action(private_key, action_json, nonce, nil, expires_after) do
-      Http.exchange_request(action, signature, nonce, nil, expires_after, opts)
+    with {:ok, signed_action, signature} <-
+           sign_action(private_key, action, nonce, nil, expires_after) do
+      Http.exchange_request(signed_action, signature, nonce, nil, expires_after, opts)
     end
   end
 
-  defp sign_action(private_key, action_json, nonce, vault_address, expires_after) do
+  defp sign_action(private_key, action, nonce, vault_address, expires_after) do
     is_mainnet = Config.mainnet?()
 
-    connection_id =
-      Signer.compute_connection_id_ex(action_json, nonce, vault_address, expires_after)
-
-    case Signer.sign_l1_action(private_key, connection_id, is_mainnet) do
-      %{"r" => r, "s" => s, "v" => v} ->
-        {:ok, %{r: r, s: s, v: v}}
+    case Signer.sign_action(
+           private_key,
+           action,
+           nonce,
+           is_mainnet,
+           vault_address,
+           expires_after
+         ) do
+      %{"r" => r, "s" => s, "v" => v, "action_json" => action_json} ->
+        {:ok, Jason.decode!(action_json, objects: :ordered_objects), %{r: r, s: s, v: v}}
 
       error ->
         {:error, {:signing_error, error}}
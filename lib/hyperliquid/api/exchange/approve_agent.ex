@@ -72,6 +72,52 @@ defmodule Hyperliquid.Api.Exchange.ApproveAgent do
     end
   end
 
+  @doc """
+  Generate a fresh agent key and approve it in one call.
+
+  The agent key is generated inside the signer NIF, the `approveAgent` action is
+  signed with the master key and submitted.
+
+  ## Options
+    - `:private_key` - Master private key for signing (falls back to config)
+    - `:expected_address` - When provided, validates the private key derives to this address
+    - `:agent_name` - Human-readable name for the agent
+
+  ## Returns
+    - `{:ok, %{agent_address: String.t(), agent_private_key: String.t(), agent_wallet: reference(), response: map()}}`
+    - `{:error, term()}` - Error details
+
+  ## Examples
+
+      {:ok, %{agent_address: address, agent_wallet: agent}} =
+        ApproveAgent.generate_and_approve(agent_name: "deploy-42")
+  """
+  @spec generate_and_approve(approve_opts()) :: {:ok, map()} | {:error, term()}
+  def generate_and_approve(opts \\ []) do
+    private_key = KeyUtils.resolve_and_validate!(opts)
+    agent_name = Keyword.get(opts, :agent_name)
+    nonce = generate_nonce()
+
+    case Signer.generate_agent(private_key, agent_name, nonce, Config.mainnet?()) do
+      %{"action_json" => action_json, "signature" => %{"r" => r, "s" => s, "v" => v}} = agent ->
+        action = Jason.decode!(action_json)
+
+        with {:ok, response} <-
+               Http.exchange_request(action, %{r: r, s: s, v: v}, nonce, nil, nil) do
+          {:ok,
+           %{
+             agent_address: agent["agent_address"],
+             agent_private_key: agent["agent_private_key"],
+             agent_wallet: agent["agent_wallet"],
+             response: response
+           }}
+        end
+
+      error ->
+        {:error, {:signing_error, error}}
+    end
+  end
+
   # ===================== Action Building =====================
 
   defp build_action(agent_address, agent_name, nonce) do
@@ -79,8 +125,8 @@ defmodule Hyperliquid.Api.Exchange.ApproveAgent do
 
     action = %{
       type: "approveAgent",
-      hyperliquidChain: if(is_mainnet, do: "Mainnet", else: "Testnet"),
-      signatureChainId: Utils.from_int(42_161),
+      hyperliquidChain: Utils.hyperliquid_chain(is_mainnet),
+      signatureChainId: Utils.signature_chain_id(is_mainnet),
       agentAddress: agent_address,
       nonce: nonce
     }
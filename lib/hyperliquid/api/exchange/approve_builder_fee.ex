@@ -42,8 +42,8 @@ defmodule Hyperliquid.Api.Exchange.ApproveBuilderFee do
 
     action = %{
       type: "approveBuilderFee",
-      hyperliquidChain: if(is_mainnet, do: "Mainnet", else: "Testnet"),
-      signatureChainId: signature_chain_id(is_mainnet),
+      hyperliquidChain: Utils.hyperliquid_chain(is_mainnet),
+      signatureChainId: Utils.signature_chain_id(is_mainnet),
       builder: builder,
       maxFeeRate: max_fee_rate,
       nonce: nonce
@@ -54,8 +54,6 @@ defmodule Hyperliquid.Api.Exchange.ApproveBuilderFee do
     Http.user_signed_request(action, signature, nonce, opts)
   end
 
-  defp signature_chain_id(_is_mainnet), do: Utils.from_int(42_161)
-
   defp generate_nonce do
     System.system_time(:millisecond)
   end
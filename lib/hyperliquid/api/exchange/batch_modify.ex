@@ -81,11 +81,11 @@ defmodule Hyperliquid.Api.Exchange.BatchModify do
 
     with {:ok, action_json} <- Jason.encode(action),
          _ <- debug("Action encoded", %{action: action}),
-         {:ok, signature} <-
+         {:ok, signed_action, signature} <-
            sign_action(private_key, action_json, nonce, vault_address, expires_after),
          _ <- debug("Action signed", %{signature: signature}),
          {:ok, response} <-
-           Http.exchange_request(action, signature, nonce, vault_address, expires_after) do
+           Http.exchange_request(signed_action, signature, nonce, vault_address, expires_after) do
       debug("Response received", %{response: response})
       {:ok, response}
     else
@@ -120,8 +120,8 @@ defmodule Hyperliquid.Api.Exchange.BatchModify do
     base = [
       {:a, order.asset},
       {:b, order.is_buy},
-      {:p, Utils.float_to_string(order.limit_px)},
-      {:s, Utils.float_to_string(order.sz)},
+      {:p, Utils.to_wire(order.limit_px)},
+      {:s, Utils.to_wire(order.sz)},
       {:r, order.reduce_only},
       {:t,
        Jason.OrderedObject.new([
@@ -143,15 +143,15 @@ defmodule Hyperliquid.Api.Exchange.BatchModify do
     base = [
       {:a, order.asset},
       {:b, order.is_buy},
-      {:p, Utils.float_to_string(order.limit_px)},
-      {:s, Utils.float_to_string(order.sz)},
+      {:p, Utils.to_wire(order.limit_px)},
+      {:s, Utils.to_wire(order.sz)},
       {:r, order.reduce_only},
       {:t,
        Jason.OrderedObject.new([
          {:trigger,
           Jason.OrderedObject.new([
             {:isMarket, order.is_market},
-            {:triggerPx, Utils.float_to_string(order.trigger_px)},
+            {:triggerPx, Utils.to_wire(order.trigger_px)},
             {:tpsl, order.tpsl}
           ])}
        ])}
@@ -178,8 +178,8 @@ defmodule Hyperliquid.Api.Exchange.BatchModify do
            vault_address,
            expires_after
          ) do
-      %{"r" => r, "s" => s, "v" => v} ->
-        {:ok, %{r: r, s: s, v: v}}
+      %{"r" => r, "s" => s, "v" => v, "action_json" => signed_json} ->
+        {:ok, Jason.decode!(signed_json, objects: :ordered_objects), %{r: r, s: s, v: v}}
 
       error ->
         {:error, {:signing_error, error}}
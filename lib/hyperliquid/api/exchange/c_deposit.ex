@@ -5,7 +5,8 @@ defmodule Hyperliquid.Api.Exchange.CDeposit do
   See: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/exchange-endpoint
   """
 
-  alias Hyperliquid.{Config, Signer, Utils}
+  alias Hyperliquid.{Config, Utils}
+  alias Hyperliquid.Api.Exchange.KeyUtils
   alias Hyperliquid.Transport.Http
 
   @doc """
@@ -26,59 +27,23 @@ defmodule Hyperliquid.Api.Exchange.CDeposit do
       {:ok, result} = CDeposit.request(private_key, 100_000_000)
   """
   def request(wei, opts \\ []) do
-    private_key = Hyperliquid.Api.Exchange.KeyUtils.resolve_private_key!(opts)
+    private_key = KeyUtils.resolve_private_key!(opts)
     nonce = generate_nonce()
     is_mainnet = Config.mainnet?()
 
-    domain = %{
-      name: "HyperliquidSignTransaction",
-      version: "1",
-      chainId: 42_161,
-      verifyingContract: "0x0000000000000000000000000000000000000000"
-    }
-
-    types = %{
-      "HyperliquidTransaction:CDeposit" => [
-        %{name: "hyperliquidChain", type: "string"},
-        %{name: "wei", type: "uint64"},
-        %{name: "nonce", type: "uint64"}
-      ]
-    }
-
-    message = %{
-      hyperliquidChain: if(is_mainnet, do: "Mainnet", else: "Testnet"),
+    action = %{
+      type: "cDeposit",
+      hyperliquidChain: Utils.hyperliquid_chain(is_mainnet),
+      signatureChainId: Utils.signature_chain_id(is_mainnet),
       wei: wei,
       nonce: nonce
     }
 
-    with {:ok, domain_json} <- Jason.encode(domain),
-         {:ok, types_json} <- Jason.encode(types),
-         {:ok, message_json} <- Jason.encode(message) do
-      sig =
-        Signer.sign_typed_data(
-          private_key,
-          domain_json,
-          types_json,
-          message_json,
-          "HyperliquidTransaction:CDeposit"
-        )
-
-      action = %{
-        type: "cDeposit",
-        hyperliquidChain: if(is_mainnet, do: "Mainnet", else: "Testnet"),
-        signatureChainId: signature_chain_id(is_mainnet),
-        wei: wei,
-        nonce: nonce
-      }
-
-      signature = %{r: sig["r"], s: sig["s"], v: sig["v"]}
-
+    with {:ok, signature} <- KeyUtils.sign_user_signed_action(private_key, action, is_mainnet) do
       Http.user_signed_request(action, signature, nonce, opts)
     end
   end
 
-  defp signature_chain_id(_is_mainnet), do: Utils.from_int(42_161)
-
   defp generate_nonce do
     System.system_time(:millisecond)
   end
@@ -5,8 +5,14 @@ defmodule Hyperliquid.Api.Exchange.KeyUtils do
   ## Private Key Resolution
 
   All exchange modules accept an optional `:private_key` in their opts keyword list.
-  When not provided, the key is resolved from `Hyperliquid.Config.secret()`.
-  If neither is available, a clear error is raised.
+  When not provided, the key is resolved from `Hyperliquid.Config.secret()`, then
+  from the encrypted keystore in `Hyperliquid.Config.keystore()`, then from the
+  external signing daemon in `Hyperliquid.Config.signer_socket()`.
+  If none is available, a clear error is raised.
+
+  Hex keys are loaded once into an opaque wallet handle (`Signer.load_wallet/1`)
+  and cached, so signing calls pass the handle instead of the raw key. A handle
+  may also be passed directly as `:private_key`.
 
   ## Address Validation (Sensitive Actions)
 
@@ -24,23 +30,136 @@ defmodule Hyperliquid.Api.Exchange.KeyUtils do
 
   alias Hyperliquid.{Config, Signer}
 
+  @wallets :hyperliquid_wallets
+  @max_wallets 64
+
   @doc """
-  Resolves the private key from opts or application config.
+  Resolves the signing wallet handle from opts or application config.
 
   ## Options
-    - `:private_key` - Explicit private key (hex string). Falls back to `Config.secret()`.
+    - `:private_key` - Explicit private key (hex string or wallet handle). Falls back to `Config.secret()`.
 
   Raises `ArgumentError` if no key is available.
   """
-  @spec resolve_private_key!(keyword()) :: String.t()
+  @spec resolve_private_key!(keyword()) :: reference()
   def resolve_private_key!(opts) do
-    case Keyword.get(opts, :private_key) || Config.secret() do
+    case Keyword.get(opts, :private_key) || Config.secret() || Config.keystore() ||
+           Config.signer_socket() do
       nil ->
         raise ArgumentError,
               "No private key provided and none configured. Pass :private_key option or set it in config."
 
-      key ->
-        key
+      %{path: path, password: password} ->
+        load_keystore!(path, password)
+
+      %{path: path} = socket ->
+        connect_signer!(path, Map.get(socket, :address))
+
+      key when is_binary(key) ->
+        load_wallet!(key)
+
+      handle ->
+        handle
+    end
+  end
+
+  @doc false
+  # Called from `Hyperliquid.Application.start/2` so the table lives as long as the application.
+  def create_wallet_cache do
+    :ets.new(@wallets, [:named_table, :set, :public, read_concurrency: true])
+    :ok
+  end
+
+  @doc """
+  Returns a cached wallet handle for a hex private key.
+
+  Handles are kept in an ETS table keyed by a SHA-256 digest of the key, so the key itself is
+  never kept as a cache key. The table holds at most #{@max_wallets} handles; rotating keys
+  evicts older ones instead of accumulating them.
+  """
+  @spec load_wallet!(String.t()) :: reference()
+  def load_wallet!(private_key) do
+    digest = :crypto.hash(:sha256, private_key)
+
+    case :ets.lookup(@wallets, digest) do
+      [{^digest, handle}] ->
+        handle
+
+      [] ->
+        case Signer.load_wallet(private_key) do
+          {:error, reason} ->
+            raise ArgumentError, "Invalid private key: #{inspect(reason)}"
+
+          handle ->
+            if :ets.info(@wallets, :size) >= @max_wallets, do: :ets.delete(@wallets, :ets.first(@wallets))
+            :ets.insert(@wallets, {digest, handle})
+            handle
+        end
+    end
+  end
+
+  @doc """
+  Returns a cached wallet handle decrypted from a v3 keystore file.
+
+  The handle is cached per keystore path together with the file's inode, size, mtime and a
+  SHA-256 digest of its contents. Replacing or re-encrypting the file decrypts it again instead
+  of reusing the old key. The password is only checked when the file is decrypted; it is never
+  part of the cache.
+  """
+  @spec load_keystore!(String.t(), String.t()) :: reference()
+  def load_keystore!(path, password) do
+    cache_key = {__MODULE__, :keystore, Path.expand(path)}
+    fingerprint = keystore_fingerprint(path)
+
+    case :persistent_term.get(cache_key, nil) do
+      {^fingerprint, handle} when fingerprint != nil ->
+        handle
+
+      _ ->
+        case Signer.load_keystore(path, password) do
+          {:error, reason} ->
+            raise ArgumentError, "Unable to load keystore #{path}: #{inspect(reason)}"
+
+          handle ->
+            if fingerprint, do: :persistent_term.put(cache_key, {fingerprint, handle})
+            handle
+        end
+    end
+  end
+
+  # nil when the file cannot be read; the NIF then reports the error and nothing is cached.
+  # mtime has one-second resolution, so the contents digest catches a rewrite within that second.
+  defp keystore_fingerprint(path) do
+    with {:ok, %File.Stat{inode: inode, size: size, mtime: mtime}} <- File.stat(path, time: :posix),
+         {:ok, contents} <- File.read(path) do
+      {inode, size, mtime, :crypto.hash(:sha256, contents)}
+    else
+      {:error, _} -> nil
+    end
+  end
+
+  @doc """
+  Returns a cached wallet handle that signs through an external daemon over a Unix socket.
+
+  When `expected_address` is given, connecting fails unless the daemon holds that key.
+  """
+  @spec connect_signer!(String.t(), String.t() | nil) :: reference()
+  def connect_signer!(path, expected_address \\ nil) do
+    cache_key = {__MODULE__, :signer_socket, Path.expand(path)}
+
+    case :persistent_term.get(cache_key, nil) do
+      nil ->
+        case Signer.connect_signer(path, expected_address, nil) do
+          {:error, reason} ->
+            raise ArgumentError, "Unable to connect to signer at #{path}: #{inspect(reason)}"
+
+          handle ->
+            :persistent_term.put(cache_key, handle)
+            handle
+        end
+
+      handle ->
+        handle
     end
   end
 
@@ -53,7 +172,7 @@ defmodule Hyperliquid.Api.Exchange.KeyUtils do
   ## Options
     - `:expected_address` - Expected checksummed Ethereum address (0x-prefixed)
   """
-  @spec validate_expected_address!(String.t(), keyword()) :: :ok
+  @spec validate_expected_address!(String.t() | reference(), keyword()) :: :ok
   def validate_expected_address!(private_key, opts) do
     case Keyword.get(opts, :expected_address) do
       nil ->
@@ -79,7 +198,7 @@ defmodule Hyperliquid.Api.Exchange.KeyUtils do
   Use this for transfer/withdrawal modules (UsdSend, Withdraw3, SpotSend,
   SendAsset, ApproveAgent, ApproveBuilderFee).
   """
-  @spec resolve_and_validate!(keyword()) :: String.t()
+  @spec resolve_and_validate!(keyword()) :: reference()
   def resolve_and_validate!(opts) do
     private_key = resolve_private_key!(opts)
     validate_expected_address!(private_key, opts)
@@ -96,12 +215,36 @@ defmodule Hyperliquid.Api.Exchange.KeyUtils do
     - `{:ok, %{r: r, s: s, v: v}}` on success
     - `{:error, {:signing_error, term()}}` on failure
   """
-  @spec sign_typed_data(String.t(), String.t(), String.t(), String.t(), String.t()) ::
-          {:ok, map()} | {:error, {:signing_error, term()}}
+  @spec sign_typed_data(
+          String.t() | reference(),
+          String.t(),
+          String.t(),
+          String.t(),
+          String.t()
+        ) :: {:ok, map()} | {:error, {:signing_error, term()}}
   def sign_typed_data(private_key, domain_json, types_json, message_json, primary_type) do
     case Signer.sign_typed_data(private_key, domain_json, types_json, message_json, primary_type) do
       %{"r" => r, "s" => s, "v" => v} -> {:ok, %{r: r, s: s, v: v}}
       error -> {:error, {:signing_error, error}}
     end
   end
+
+  @doc """
+  Signs a user-signed action (e.g. `sendAsset`, `cDeposit`) and returns the signature components.
+
+  Wraps `Signer.sign_user_signed_action/3`, which derives the `HyperliquidTransaction:*`
+  EIP-712 struct from the action's `type` and fields.
+
+  ## Returns
+    - `{:ok, %{r: r, s: s, v: v}}` on success
+    - `{:error, {:signing_error, term()}}` on failure
+  """
+  @spec sign_user_signed_action(String.t() | reference(), map() | Jason.OrderedObject.t(), boolean()) ::
+          {:ok, map()} | {:error, {:signing_error, term()}}
+  def sign_user_signed_action(private_key, action, is_mainnet) do
+    case Signer.sign_user_signed_action(private_key, action, is_mainnet) do
+      %{"r" => r, "s" => s, "v" => v} -> {:ok, %{r: r, s: s, v: v}}
+      error -> {:error, {:signing_error, error}}
+    end
+  end
 end
@@ -0,0 +1,168 @@
+defmodule Hyperliquid.Api.Exchange.MultiSig do
+  @moduledoc """
+  Submit actions on behalf of a multi-sig account (see `ConvertToMultiSigUser`).
+
+  Every co-signer signs the inner action with `sign_l1_action/5`, or `sign_user_signed_action/4`
+  for a user-signed action such as `usdSend` or `withdraw3`. The outer signer, one of the
+  co-signers, collects those signatures and submits them with `request/5`, which wraps them into a
+  `multiSig` action and signs it. All signatures must be over the same nonce, vault address and
+  expiresAfter; for a user-signed action the nonce is the action's own `time` or `nonce`.
+
+      nonce = System.system_time(:millisecond)
+      action = %{type: "scheduleCancel", time: nonce + 60_000}
+
+      {:ok, sig_a} = MultiSig.sign_l1_action(action, multi_sig_user, outer_signer, nonce, private_key: key_a)
+      {:ok, sig_b} = MultiSig.sign_l1_action(action, multi_sig_user, outer_signer, nonce, private_key: key_b)
+      {:ok, result} = MultiSig.request(multi_sig_user, action, [sig_a, sig_b], nonce, private_key: outer_key)
+
+  See: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/exchange-endpoint
+  """
+
+  alias Hyperliquid.{Config, Signer}
+  alias Hyperliquid.Api.Exchange.KeyUtils
+  alias Hyperliquid.Transport.Http
+
+  @doc """
+  Co-signer signature over an L1 action of a multi-sig account.
+
+  ## Parameters
+    - `action`: Inner L1 action (map, keyword list or `Jason.OrderedObject`)
+    - `multi_sig_user`: Address of the multi-sig account
+    - `outer_signer`: Address of the co-signer who will submit
+    - `nonce`: Nonce shared by every signature
+    - `opts`: Optional parameters
+
+  ## Options
+    - `:private_key` - Co-signer key (falls back to config)
+    - `:vault_address` - Act for a vault
+    - `:expires_after` - expiresAfter timestamp in milliseconds
+
+  ## Returns
+    - `{:ok, %{r: r, s: s, v: v}}` - Signature to hand to the outer signer
+    - `{:error, {:signing_error, term()}}` - Error details
+  """
+  def sign_l1_action(action, multi_sig_user, outer_signer, nonce, opts \\ []) do
+    private_key = KeyUtils.resolve_private_key!(opts)
+
+    case Signer.sign_multi_sig_l1_payload(
+           private_key,
+           action,
+           multi_sig_user,
+           outer_signer,
+           nonce,
+           Config.mainnet?(),
+           Keyword.get(opts, :vault_address),
+           Keyword.get(opts, :expires_after)
+         ) do
+      %{"r" => r, "s" => s, "v" => v} -> {:ok, %{r: r, s: s, v: v}}
+      error -> {:error, {:signing_error, error}}
+    end
+  end
+
+  @doc """
+  Co-signer signature over a user-signed action of a multi-sig account.
+
+  ## Parameters
+    - `action`: Inner user-signed action, e.g. `%{type: "usdSend", destination: ..., amount: ..., time: ...}`
+    - `multi_sig_user`: Address of the multi-sig account
+    - `outer_signer`: Address of the co-signer who will submit
+    - `opts`: Optional parameters
+
+  ## Options
+    - `:private_key` - Co-signer key (falls back to config)
+
+  ## Returns
+    - `{:ok, %{r: r, s: s, v: v}}` - Signature to hand to the outer signer
+    - `{:error, {:signing_error, term()}}` - Error details
+  """
+  def sign_user_signed_action(action, multi_sig_user, outer_signer, opts \\ []) do
+    private_key = KeyUtils.resolve_private_key!(opts)
+
+    case Signer.sign_multi_sig_user_signed_payload(private_key, action, multi_sig_user, outer_signer, Config.mainnet?()) do
+      %{"r" => r, "s" => s, "v" => v} -> {:ok, %{r: r, s: s, v: v}}
+      error -> {:error, {:signing_error, error}}
+    end
+  end
+
+  @doc """
+  Wrap co-signer signatures into a `multiSig` action, sign it as the outer signer and submit it.
+
+  ## Parameters
+    - `multi_sig_user`: Address of the multi-sig account
+    - `action`: The inner action the signatures are over
+    - `signatures`: Co-signer signatures (`%{r: r, s: s, v: v}` maps)
+    - `nonce`: Nonce shared by every signature
+    - `opts`: Optional parameters
+
+  ## Options
+    - `:private_key` - Outer signer key (falls back to config)
+    - `:vault_address` - Act for a vault
+    - `:expires_after` - expiresAfter timestamp in milliseconds
+
+  ## Returns
+    - `{:ok, response}` - Result
+    - `{:error, term()}` - Error details
+  """
+  def request(multi_sig_user, action, signatures, nonce, opts \\ []) do
+    private_key = KeyUtils.resolve_private_key!(opts)
+    vault_address = Keyword.get(opts, :vault_address)
+    expires_after = Keyword.get(opts, :expires_after)
+
+    case Signer.finalize_multi_sig_action(
+           private_key,
+           multi_sig_user,
+           action,
+           signatures,
+           nonce,
+           Config.mainnet?(),
+           vault_address,
+           expires_after
+         ) do
+      %{"r" => r, "s" => s, "v" => v, "action_json" => action_json} ->
+        multi_sig_action = Jason.decode!(action_json, objects: :ordered_objects)
+        Http.exchange_request(multi_sig_action, %{r: r, s: s, v: v}, nonce, vault_address, expires_after, opts)
+
+      error ->
+        {:error, {:signing_error, error}}
+    end
+  end
+
+  @doc """
+  Finalize a pending multi-sig bundle as its outer signer and submit it.
+
+  Co-signers on other machines sign the bundle with `Hyperliquid.Signer.sign_multi_sig_bundle/2`
+  or the `multi_sig_bundle` command line tool; once it holds enough signatures it can be submitted
+  from here. The nonce, vault address and expiresAfter come from the bundle.
+
+  ## Parameters
+    - `bundle`: Bundle in JSON or compact form (`Hyperliquid.Signer.new_multi_sig_bundle/9`)
+    - `opts`: Optional parameters
+
+  ## Options
+    - `:private_key` - Outer signer key (falls back to config)
+
+  ## Returns
+    - `{:ok, response}` - Result
+    - `{:error, term()}` - Error details
+  """
+  def request_bundle(bundle, opts \\ []) do
+    private_key = KeyUtils.resolve_private_key!(opts)
+
+    case Signer.finalize_multi_sig_bundle(private_key, bundle) do
+      %{"r" => r, "s" => s, "v" => v, "action_json" => action_json, "nonce" => nonce} = signed ->
+        multi_sig_action = Jason.decode!(action_json, objects: :ordered_objects)
+
+        Http.exchange_request(
+          multi_sig_action,
+          %{r: r, s: s, v: v},
+          nonce,
+          signed["vault_address"],
+          signed["expires_after"],
+          opts
+        )
+
+      error ->
+        {:error, {:signing_error, error}}
+    end
+  end
+end
@@ -524,11 +524,11 @@ defmodule Hyperliquid.Api.Exchange.Order do
 
     with {:ok, action_json} <- Jason.encode(action),
          _ <- debug("Action encoded", %{action: action}),
-         {:ok, signature} <-
+         {:ok, signed_action, signature} <-
            sign_action(private_key, action_json, nonce, vault_address, expires_after),
          _ <- debug("Action signed", %{signature: signature}),
          {:ok, response} <-
-           Http.exchange_request(action, signature, nonce, vault_address, expires_after) do
+           Http.exchange_request(signed_action, signature, nonce, vault_address, expires_after) do
       debug("Response received", %{response: response})
       {:ok, response}
     else
@@ -561,8 +561,8 @@ defmodule Hyperliquid.Api.Exchange.Order do
     base = %{
       a: order.asset,
       b: order.is_buy,
-      p: Utils.float_to_string(order.limit_px),
-      s: Utils.float_to_string(order.sz),
+      p: Utils.to_wire(order.limit_px),
+      s: Utils.to_wire(order.sz),
       r: order.reduce_only,
       t: %{
         limit: %{
@@ -578,13 +578,13 @@ defmodule Hyperliquid.Api.Exchange.Order do
     base = %{
       a: order.asset,
       b: order.is_buy,
-      p: Utils.float_to_string(order.limit_px),
-      s: Utils.float_to_string(order.sz),
+      p: Utils.to_wire(order.limit_px),
+      s: Utils.to_wire(order.sz),
       r: order.reduce_only,
       t: %{
         trigger: %{
           isMarket: order.is_market,
-          triggerPx: Utils.float_to_string(order.trigger_px),
+          triggerPx: Utils.to_wire(order.trigger_px),
           tpsl: order.tpsl
         }
       }
@@ -613,8 +613,8 @@ defmodule Hyperliquid.Api.Exchange.Order do
            vault_address,
            expires_after
          ) do
-      %{"r" => r, "s" => s, "v" => v} ->
-        {:ok, %{r: r, s: s, v: v}}
+      %{"r" => r, "s" => s, "v" => v, "action_json" => signed_json} ->
+        {:ok, Jason.decode!(signed_json, objects: :ordered_objects), %{r: r, s: s, v: v}}
 
       error ->
         {:error, {:signing_error, error}}
@@ -5,7 +5,7 @@ defmodule Hyperliquid.Api.Exchange.SendAsset do
   See: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/exchange-endpoint#send-asset
   """
 
-  alias Hyperliquid.{Config, Signer, Utils}
+  alias Hyperliquid.{Config, Utils}
   alias Hyperliquid.Api.Exchange.KeyUtils
   alias Hyperliquid.Transport.Http
 
@@ -57,27 +57,14 @@ defmodule Hyperliquid.Api.Exchange.SendAsset do
     is_mainnet = Config.mainnet?()
     from_sub_account = Keyword.get(opts, :from_sub_account, "")
 
-    sig =
-      sign_send_asset(
-        private_key,
-        destination,
-        source_dex,
-        destination_dex,
-        token,
-        amount,
-        from_sub_account,
-        time,
-        is_mainnet
-      )
-
     # IMPORTANT: Use OrderedObject for correct field order in hash calculation
     # Field order: type, signatureChainId, hyperliquidChain, destination, sourceDex,
     #              destinationDex, token, amount, fromSubAccount, nonce
     action =
       Jason.OrderedObject.new([
         {:type, "sendAsset"},
-        {:signatureChainId, signature_chain_id(is_mainnet)},
-        {:hyperliquidChain, if(is_mainnet, do: "Mainnet", else: "Testnet")},
+        {:signatureChainId, Utils.signature_chain_id(is_mainnet)},
+        {:hyperliquidChain, Utils.hyperliquid_chain(is_mainnet)},
         {:destination, destination},
         {:sourceDex, source_dex},
         {:destinationDex, destination_dex},
@@ -87,76 +74,11 @@ defmodule Hyperliquid.Api.Exchange.SendAsset do
         {:nonce, time}
       ])
 
-    signature = %{r: sig["r"], s: sig["s"], v: sig["v"]}
-
-    Http.user_signed_request(action, signature, time, opts)
+    with {:ok, signature} <- KeyUtils.sign_user_signed_action(private_key, action, is_mainnet) do
+      Http.user_signed_request(action, signature, time, opts)
+    end
   end
 
-  defp sign_send_asset(
-         private_key,
-         destination,
-         source_dex,
-         destination_dex,
-         token,
-         amount,
-         from_sub_account,
-         nonce,
-         is_mainnet
-       ) do
-    # EIP-712 domain — Hyperliquid uses chainId 42161 (Arbitrum One) for BOTH
-    # mainnet and testnet. The domain name must be "HyperliquidSignTransaction".
-    domain = %{
-      name: "HyperliquidSignTransaction",
-      version: "1",
-      chainId: 42_161,
-      verifyingContract: "0x0000000000000000000000000000000000000000"
-    }
-
-    # EIP-712 types for SendAsset
-    types = %{
-      "EIP712Domain" => [
-        %{name: "name", type: "string"},
-        %{name: "version", type: "string"},
-        %{name: "chainId", type: "uint256"},
-        %{name: "verifyingContract", type: "address"}
-      ],
-      "HyperliquidTransaction:SendAsset" => [
-        %{name: "hyperliquidChain", type: "string"},
-        %{name: "destination", type: "string"},
-        %{name: "sourceDex", type: "string"},
-        %{name: "destinationDex", type: "string"},
-        %{name: "token", type: "string"},
-        %{name: "amount", type: "string"},
-        %{name: "fromSubAccount", type: "string"},
-        %{name: "nonce", type: "uint64"}
-      ]
-    }
-
-    # Message to sign
-    message = %{
-      hyperliquidChain: if(is_mainnet, do: "Mainnet", else: "Testnet"),
-      destination: destination,
-      sourceDex: source_dex,
-      destinationDex: destination_dex,
-      token: token,
-      amount: amount,
-      fromSubAccount: from_sub_account,
-      nonce: nonce
-    }
-
-    # Convert to JSON for signing
-    domain_json = Jason.encode!(domain)
-    types_json = Jason.encode!(types)
-    message_json = Jason.encode!(message)
-    primary_type = "HyperliquidTransaction:SendAsset"
-
-    Signer.sign_typed_data(private_key, domain_json, types_json, message_json, primary_type)
-  end
-
-  # Hyperliquid uses signatureChainId 42161 (Arbitrum One) for BOTH mainnet and testnet.
-  # The network distinction is conveyed via the hyperliquidChain field ("Mainnet"/"Testnet").
-  defp signature_chain_id(_is_mainnet), do: Utils.from_int(42_161)
-
   defp generate_nonce do
     System.system_time(:millisecond)
   end
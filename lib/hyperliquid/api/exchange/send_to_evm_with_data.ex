@@ -48,74 +48,27 @@ defmodule Hyperliquid.Api.Exchange.SendToEvmWithData do
     is_mainnet = Config.mainnet?()
     address_encoding = Keyword.get(opts, :address_encoding, "hex")
 
-    domain = %{
-      name: "HyperliquidSignTransaction",
-      version: "1",
-      chainId: 42_161,
-      verifyingContract: "0x0000000000000000000000000000000000000000"
-    }
-
-    types = %{
-      "HyperliquidTransaction:SendToEvmWithData" => [
-        %{name: "hyperliquidChain", type: "string"},
-        %{name: "token", type: "string"},
-        %{name: "amount", type: "string"},
-        %{name: "sourceDex", type: "string"},
-        %{name: "destinationRecipient", type: "string"},
-        %{name: "addressEncoding", type: "string"},
-        %{name: "destinationChainId", type: "uint64"},
-        %{name: "gasLimit", type: "uint64"},
-        %{name: "data", type: "string"},
-        %{name: "nonce", type: "uint64"}
-      ]
-    }
-
-    message = %{
-      hyperliquidChain: if(is_mainnet, do: "Mainnet", else: "Testnet"),
-      token: token,
-      amount: amount,
-      sourceDex: source_dex,
-      destinationRecipient: destination_recipient,
-      addressEncoding: address_encoding,
-      destinationChainId: destination_chain_id,
-      gasLimit: gas_limit,
-      data: data,
-      nonce: nonce
-    }
-
-    with {:ok, domain_json} <- Jason.encode(domain),
-         {:ok, types_json} <- Jason.encode(types),
-         {:ok, message_json} <- Jason.encode(message),
-         {:ok, signature} <-
-           KeyUtils.sign_typed_data(
-             private_key,
-             domain_json,
-             types_json,
-             message_json,
-             "HyperliquidTransaction:SendToEvmWithData"
-           ) do
-      action =
-        Jason.OrderedObject.new([
-          {:type, "sendToEvmWithData"},
-          {:signatureChainId, signature_chain_id()},
-          {:hyperliquidChain, if(is_mainnet, do: "Mainnet", else: "Testnet")},
-          {:token, token},
-          {:amount, amount},
-          {:sourceDex, source_dex},
-          {:destinationRecipient, destination_recipient},
-          {:addressEncoding, address_encoding},
-          {:destinationChainId, destination_chain_id},
-          {:gasLimit, gas_limit},
-          {:data, data},
-          {:nonce, nonce}
-        ])
+    action =
+      Jason.OrderedObject.new([
+        {:type, "sendToEvmWithData"},
+        {:signatureChainId, Utils.signature_chain_id(is_mainnet)},
+        {:hyperliquidChain, Utils.hyperliquid_chain(is_mainnet)},
+        {:token, token},
+        {:amount, amount},
+        {:sourceDex, source_dex},
+        {:destinationRecipient, destination_recipient},
+        {:addressEncoding, address_encoding},
+        {:destinationChainId, destination_chain_id},
+        {:gasLimit, gas_limit},
+        {:data, data},
+        {:nonce, nonce}
+      ])
 
+    with {:ok, signature} <- KeyUtils.sign_user_signed_action(private_key, action, is_mainnet) do
       Http.user_signed_request(action, signature, nonce, opts)
     end
   end
 
-  defp signature_chain_id, do: Utils.from_int(42_161)
-
   defp generate_nonce do
     System.system_time(:millisecond)
   end
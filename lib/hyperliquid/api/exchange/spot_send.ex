@@ -47,8 +47,8 @@ defmodule Hyperliquid.Api.Exchange.SpotSend do
     action =
       Jason.OrderedObject.new([
         {:type, "spotSend"},
-        {:signatureChainId, signature_chain_id(is_mainnet)},
-        {:hyperliquidChain, if(is_mainnet, do: "Mainnet", else: "Testnet")},
+        {:signatureChainId, Utils.signature_chain_id(is_mainnet)},
+        {:hyperliquidChain, Utils.hyperliquid_chain(is_mainnet)},
         {:destination, destination},
         {:token, token},
         {:amount, amount},
@@ -60,8 +60,6 @@ defmodule Hyperliquid.Api.Exchange.SpotSend do
     Http.user_signed_request(action, signature, time, opts)
   end
 
-  defp signature_chain_id(_is_mainnet), do: Utils.from_int(42_161)
-
   defp generate_nonce do
     System.system_time(:millisecond)
   end
@@ -38,58 +38,21 @@ defmodule Hyperliquid.Api.Exchange.TokenDelegate do
     nonce = generate_nonce()
     is_mainnet = Config.mainnet?()
 
-    domain = %{
-      name: "HyperliquidSignTransaction",
-      version: "1",
-      chainId: 42_161,
-      verifyingContract: "0x0000000000000000000000000000000000000000"
-    }
-
-    types = %{
-      "HyperliquidTransaction:TokenDelegate" => [
-        %{name: "hyperliquidChain", type: "string"},
-        %{name: "validator", type: "string"},
-        %{name: "isUndelegate", type: "bool"},
-        %{name: "wei", type: "uint64"},
-        %{name: "nonce", type: "uint64"}
-      ]
-    }
-
-    message = %{
-      hyperliquidChain: if(is_mainnet, do: "Mainnet", else: "Testnet"),
+    action = %{
+      type: "tokenDelegate",
+      hyperliquidChain: Utils.hyperliquid_chain(is_mainnet),
+      signatureChainId: Utils.signature_chain_id(is_mainnet),
       validator: validator,
       isUndelegate: is_undelegate,
       wei: wei,
       nonce: nonce
     }
 
-    with {:ok, domain_json} <- Jason.encode(domain),
-         {:ok, types_json} <- Jason.encode(types),
-         {:ok, message_json} <- Jason.encode(message),
-         {:ok, signature} <-
-           KeyUtils.sign_typed_data(
-             private_key,
-             domain_json,
-             types_json,
-             message_json,
-             "HyperliquidTransaction:TokenDelegate"
-           ) do
-      action = %{
-        type: "tokenDelegate",
-        hyperliquidChain: if(is_mainnet, do: "Mainnet", else: "Testnet"),
-        signatureChainId: signature_chain_id(),
-        validator: validator,
-        isUndelegate: is_undelegate,
-        wei: wei,
-        nonce: nonce
-      }
-
+    with {:ok, signature} <- KeyUtils.sign_user_signed_action(private_key, action, is_mainnet) do
       Http.user_signed_request(action, signature, nonce, opts)
     end
   end
 
-  defp signature_chain_id, do: Utils.from_int(42_161)
-
   defp generate_nonce do
     System.system_time(:millisecond)
   end
@@ -47,9 +47,9 @@ defmodule Hyperliquid.Api.Exchange.UpdateLeverage do
     }
 
     with {:ok, action_json} <- Jason.encode(action),
-         {:ok, signature} <-
+         {:ok, signed_action, signature} <-
            sign_action(private_key, action_json, nonce, vault_address, expires_after) do
-      Http.exchange_request(action, signature, nonce, vault_address, expires_after, opts)
+      Http.exchange_request(signed_action, signature, nonce, vault_address, expires_after, opts)
     end
   end
 
@@ -64,8 +64,8 @@ defmodule Hyperliquid.Api.Exchange.UpdateLeverage do
            vault_address,
            expires_after
          ) do
-      %{"r" => r, "s" => s, "v" => v} ->
-        {:ok, %{r: r, s: s, v: v}}
+      %{"r" => r, "s" => s, "v" => v, "action_json" => signed_json} ->
+        {:ok, Jason.decode!(signed_json, objects: :ordered_objects), %{r: r, s: s, v: v}}
 
       error ->
         {:error, {:signing_error, error}}
@@ -6,19 +6,23 @@ defmodule Hyperliquid.Api.Exchange.UsdClassTransfer do
   """
 
   alias Hyperliquid.{Config, Signer, Utils}
+  alias Hyperliquid.Api.Exchange.KeyUtils
   alias Hyperliquid.Transport.Http
 
   @doc """
   Transfer USD between spot and perp accounts.
 
+  This is a user-signed action: it is signed as the EIP-712
+  `HyperliquidTransaction:UsdClassTransfer` struct, not as an L1 action.
+
   ## Parameters
-    - `private_key`: Private key for signing (hex string)
     - `amount`: Amount to transfer as string
     - `to_perp`: true to transfer to perp, false to transfer to spot
     - `opts`: Optional parameters
 
   ## Options
-    - `:vault_address` - Transfer for a vault
+    - `:private_key` - Private key for signing (falls back to config)
+    - `:vault_address` - Sub-account to transfer for; signed as `"<amount> subaccount:<address>"`
 
   ## Returns
     - `{:ok, response}` - Transfer result
@@ -27,39 +31,20 @@ defmodule Hyperliquid.Api.Exchange.UsdClassTransfer do
   ## Examples
 
       # Transfer to perp
-      {:ok, result} = UsdClassTransfer.request(private_key, "100.0", true)
+      {:ok, result} = UsdClassTransfer.request("100.0", true)
 
-      # Transfer to spot
-      {:ok, result} = UsdClassTransfer.request(private_key, "100.0", false)
+      # Transfer to spot for a sub-account
+      {:ok, result} = UsdClassTransfer.request("100.0", false, vault_address: "0x...")
   """
   def request(amount, to_perp, opts \\ []) do
-    private_key = Hyperliquid.Api.Exchange.KeyUtils.resolve_private_key!(opts)
+    private_key = KeyUtils.resolve_private_key!(opts)
     vault_address = Keyword.get(opts, :vault_address)
     nonce = generate_nonce()
-    expires_after = Config.expires_after()
-
-    action = %{
-      type: "usdClassTransfer",
-      amount: Utils.float_to_string(amount),
-      toPerp: to_perp
-    }
-
-    with {:ok, action_json} <- Jason.encode(action),
-         {:ok, signature} <-
-           sign_action(private_key, action_json, nonce, vault_address, expires_after) do
-      Http.exchange_request(action, signature, nonce, vault_address, expires_after, opts)
-    end
-  end
-
-  defp sign_action(private_key, action_json, nonce, vault_address, expires_after) do
     is_mainnet = Config.mainnet?()
 
-    connection_id =
-      Signer.compute_connection_id_ex(action_json, nonce, vault_address, expires_after)
-
-    case Signer.sign_l1_action(private_key, connection_id, is_mainnet) do
-      %{"r" => r, "s" => s, "v" => v} ->
-        {:ok, %{r: r, s: s, v: v}}
+    case Signer.sign_usd_class_transfer(private_key, Utils.to_wire(amount), to_perp, nonce, is_mainnet, vault_address) do
+      %{"r" => r, "s" => s, "v" => v, "action_json" => action_json} ->
+        Http.user_signed_request(Jason.decode!(action_json), %{r: r, s: s, v: v}, nonce, opts)
 
       error ->
         {:error, {:signing_error, error}}
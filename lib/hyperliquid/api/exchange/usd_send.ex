@@ -47,8 +47,8 @@ defmodule Hyperliquid.Api.Exchange.UsdSend do
     action =
       Jason.OrderedObject.new([
         {:type, "usdSend"},
-        {:signatureChainId, signature_chain_id(is_mainnet)},
-        {:hyperliquidChain, if(is_mainnet, do: "Mainnet", else: "Testnet")},
+        {:signatureChainId, Utils.signature_chain_id(is_mainnet)},
+        {:hyperliquidChain, Utils.hyperliquid_chain(is_mainnet)},
         {:destination, destination},
         {:amount, amount},
         {:time, time}
@@ -59,9 +59,6 @@ defmodule Hyperliquid.Api.Exchange.UsdSend do
     Http.user_signed_request(action, signature, time, opts)
   end
 
-  # Hyperliquid uses signatureChainId 42161 (Arbitrum One) for BOTH mainnet and testnet.
-  defp signature_chain_id(_is_mainnet), do: Utils.from_int(42_161)
-
   defp generate_nonce do
     System.system_time(:millisecond)
   end
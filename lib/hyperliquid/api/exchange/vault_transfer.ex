@@ -5,7 +5,7 @@ defmodule Hyperliquid.Api.Exchange.VaultTransfer do
   See: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/exchange-endpoint
   """
 
-  alias Hyperliquid.{Config, Signer, Utils}
+  alias Hyperliquid.{Config, Signer}
   alias Hyperliquid.Transport.Http
 
   @doc """
@@ -15,7 +15,7 @@ defmodule Hyperliquid.Api.Exchange.VaultTransfer do
     - `private_key`: Private key for signing (hex string)
     - `vault_address`: Vault address
     - `is_deposit`: true for deposit, false for withdrawal
-    - `usd`: Amount in USD as string
+    - `usd`: Amount in raw units (float * 1e6), as an integer
     - `opts`: Optional parameters
 
   ## Returns
@@ -24,13 +24,13 @@ defmodule Hyperliquid.Api.Exchange.VaultTransfer do
 
   ## Examples
 
-      # Deposit to vault
-      {:ok, result} = VaultTransfer.request(private_key, "0x...", true, "1000.0")
+      # Deposit 1000 USDC to vault
+      {:ok, result} = VaultTransfer.request("0x...", true, 1_000_000_000)
 
-      # Withdraw from vault
-      {:ok, result} = VaultTransfer.request(private_key, "0x...", false, "500.0")
+      # Withdraw 500 USDC from vault
+      {:ok, result} = VaultTransfer.request("0x...", false, 500_000_000)
   """
-  def request(vault_address, is_deposit, usd, opts \\ []) do
+  def request(vault_address, is_deposit, usd, opts \\ []) when is_integer(usd) do
     private_key = Hyperliquid.Api.Exchange.KeyUtils.resolve_private_key!(opts)
     nonce = generate_nonce()
     expires_after = Config.expires_after()
@@ -39,24 +39,28 @@ defmodule Hyperliquid.Api.Exchange.VaultTransfer do
       type: "vaultTransfer",
       vaultAddress: vault_address,
       isDeposit: is_deposit,
-      usd: Utils.float_to_string(usd)
+      usd: usd
     }
 
-    with {:ok, action_json} <- Jason.encode(action),
-         {:ok, signature} <- sign_action(private_key, action_json, nonce, nil, expires_after) do
-      Http.exchange_request(action, signature, nonce, nil, expires_after, opts)
+    with {:ok, signed_action, signature} <-
+           sign_action(private_key, action, nonce, nil, expires_after) do
+      Http.exchange_request(signed_action, signature, nonce, nil, expires_after, opts)
     end
   end
 
-  defp sign_action(private_key, action_json, nonce, vault_address, expires_after) do
+  defp sign_action(private_key, action, nonce, vault_address, expires_after) do
     is_mainnet = Config.mainnet?()
 
-    connection_id =
-      Signer.compute_connection_id_ex(action_json, nonce, vault_address, expires_after)
-
-    case Signer.sign_l1_action(private_key, connection_id, is_mainnet) do
-      %{"r" => r, "s" => s, "v" => v} ->
-        {:ok, %{r: r, s: s, v: v}}
+    case Signer.sign_action(
+           private_key,
+           action,
+           nonce,
+           is_mainnet,
+           vault_address,
+           expires_after
+         ) do
+      %{"r" => r, "s" => s, "v" => v, "action_json" => action_json} ->
+        {:ok, Jason.decode!(action_json, objects: :ordered_objects), %{r: r, s: s, v: v}}
 
       error ->
         {:error, {:signing_error, error}}
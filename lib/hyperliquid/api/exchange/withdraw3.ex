@@ -43,8 +43,8 @@ defmodule Hyperliquid.Api.Exchange.Withdraw3 do
 
     action = %{
       type: "withdraw3",
-      hyperliquidChain: if(is_mainnet, do: "Mainnet", else: "Testnet"),
-      signatureChainId: signature_chain_id(is_mainnet),
+      hyperliquidChain: Utils.hyperliquid_chain(is_mainnet),
+      signatureChainId: Utils.signature_chain_id(is_mainnet),
       destination: destination,
       amount: amount,
       time: time
@@ -55,8 +55,6 @@ defmodule Hyperliquid.Api.Exchange.Withdraw3 do
     Http.user_signed_request(action, signature, time, opts)
   end
 
-  defp signature_chain_id(_is_mainnet), do: Utils.from_int(42_161)
-
   defp generate_nonce do
     System.system_time(:millisecond)
   end
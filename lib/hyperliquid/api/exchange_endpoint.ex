@@ -157,18 +157,17 @@ defmodule Hyperliquid.Api.ExchangeEndpoint do
           )
 
           result =
-            with {:ok, action_json} <- Jason.encode(action),
-                 {:ok, signature} <-
+            with {:ok, signed_action, signature} <-
                    sign_l1_action(
                      private_key,
-                     action_json,
+                     action,
                      nonce,
                      vault_address,
                      expires_after,
                      is_mainnet
                    ) do
               Hyperliquid.Transport.Http.exchange_request(
-                action,
+                signed_action,
                 signature,
                 nonce,
                 vault_address,
@@ -197,25 +196,29 @@ defmodule Hyperliquid.Api.ExchangeEndpoint do
           result
         end
 
+        # Signs the typed action, so the signing policy can inspect it, and returns the
+        # canonical action that was signed for submission.
         defp sign_l1_action(
                private_key,
-               action_json,
+               action,
                nonce,
                vault_address,
                expires_after,
                is_mainnet
              ) do
-          connection_id =
-            Hyperliquid.Signer.compute_connection_id_ex(
-              action_json,
-              nonce,
-              vault_address,
-              expires_after
-            )
-
-          case Hyperliquid.Signer.sign_l1_action(private_key, connection_id, is_mainnet) do
-            %{"r" => r, "s" => s, "v" => v} -> {:ok, %{r: r, s: s, v: v}}
-            error -> {:error, {:signing_error, error}}
+          case Hyperliquid.Signer.sign_action(
+                 private_key,
+                 action,
+                 nonce,
+                 is_mainnet,
+                 vault_address,
+                 expires_after
+               ) do
+            %{"r" => r, "s" => s, "v" => v, "action_json" => action_json} ->
+              {:ok, Jason.decode!(action_json, objects: :ordered_objects), %{r: r, s: s, v: v}}
+
+            error ->
+              {:error, {:signing_error, error}}
           end
         end
 
@@ -279,18 +282,17 @@ defmodule Hyperliquid.Api.ExchangeEndpoint do
           )
 
           result =
-            with {:ok, action_json} <- Jason.encode(action),
-                 {:ok, signature} <-
+            with {:ok, signed_action, signature} <-
                    sign_l1_action(
                      private_key,
-                     action_json,
+                     action,
                      nonce,
                      vault_address,
                      expires_after,
                      is_mainnet
                    ) do
               Hyperliquid.Transport.Http.exchange_request(
-                action,
+                signed_action,
                 signature,
                 nonce,
                 vault_address,
@@ -321,23 +323,25 @@ defmodule Hyperliquid.Api.ExchangeEndpoint do
 
         defp sign_l1_action(
                private_key,
-               action_json,
+               action,
                nonce,
                vault_address,
                expires_after,
                is_mainnet
              ) do
-          connection_id =
-            Hyperliquid.Signer.compute_connection_id_ex(
-              action_json,
-              nonce,
-              vault_address,
-              expires_after
-            )
-
-          case Hyperliquid.Signer.sign_l1_action(private_key, connection_id, is_mainnet) do
-            %{"r" => r, "s" => s, "v" => v} -> {:ok, %{r: r, s: s, v: v}}
-            error -> {:error, {:signing_error, error}}
+          case Hyperliquid.Signer.sign_action(
+                 private_key,
+                 action,
+                 nonce,
+                 is_mainnet,
+                 vault_address,
+                 expires_after
+               ) do
+            %{"r" => r, "s" => s, "v" => v, "action_json" => action_json} ->
+              {:ok, Jason.decode!(action_json, objects: :ordered_objects), %{r: r, s: s, v: v}}
+
+            error ->
+              {:error, {:signing_error, error}}
           end
         end
 
@@ -366,8 +370,11 @@ defmodule Hyperliquid.Api.ExchangeEndpoint do
                vault_address,
                expires_after
              ) do
-          %{"r" => r, "s" => s, "v" => v} -> {:ok, %{r: r, s: s, v: v}}
-          error -> {:error, {:signing_error, error}}
+          %{"r" => r, "s" => s, "v" => v, "action_json" => signed_json} ->
+            {:ok, Jason.decode!(signed_json, objects: :ordered_objects), %{r: r, s: s, v: v}}
+
+          error ->
+            {:error, {:signing_error, error}}
         end
       end
 
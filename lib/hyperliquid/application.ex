@@ -16,6 +16,11 @@ defmodule Hyperliquid.Application do
       validate_db_dependencies!()
     end
 
+    load_network_profile!()
+    Hyperliquid.Api.Exchange.KeyUtils.create_wallet_cache()
+    load_signing_policy!()
+    open_audit_log!()
+
     # Core children that always start
     # Order: PubSub, Cachex, Warmer (needs Cachex), Registry, WebSocket.Supervisor
     core_children =
@@ -61,6 +66,43 @@ defmodule Hyperliquid.Application do
     Supervisor.start_link(children, opts)
   end
 
+  defp load_network_profile! do
+    profile = Config.network_profile()
+
+    case Hyperliquid.Signer.set_network_profile(profile) do
+      :ok -> :ok
+      {:error, reason} -> raise "invalid network profile #{inspect(profile)}: #{reason}"
+    end
+  end
+
+  defp load_signing_policy! do
+    case Config.signing_policy() do
+      nil ->
+        :ok
+
+      path ->
+        format = if Path.extname(path) == ".json", do: "json", else: "toml"
+
+        case Hyperliquid.Signer.set_policy(File.read!(path), format) do
+          :ok -> :ok
+          {:error, reason} -> raise "invalid signing policy #{path}: #{reason}"
+        end
+    end
+  end
+
+  defp open_audit_log! do
+    case Config.audit_log() do
+      nil ->
+        :ok
+
+      path ->
+        case Hyperliquid.Signer.open_audit_log(path) do
+          :ok -> :ok
+          {:error, reason} -> raise "cannot open signing audit log #{path}: #{reason}"
+        end
+    end
+  end
+
   defp validate_db_dependencies! do
     required_apps = [:ecto_sql, :postgrex, :phoenix_ecto]
 
@@ -319,11 +319,19 @@ defmodule Hyperliquid.Cache do
       cache_put(:decimal_map, decimal_map)
       cache_put(:asset_to_sz_decimals, asset_to_sz_decimals)
       cache_put(:asset_to_price_decimals, asset_to_price_decimals)
+      push_sz_decimals(asset_to_sz_decimals)
     end
 
     :ok
   end
 
+  # Hand the asset -> szDecimals table to the signer NIF, which validates order prices and
+  # sizes against it when `Config.enforce_tick_sizes/0` is set.
+  defp push_sz_decimals(asset_to_sz_decimals) do
+    table = for {asset, sz_dec} <- asset_to_sz_decimals, is_integer(sz_dec), into: %{}, do: {asset, sz_dec}
+    Hyperliquid.Signer.set_sz_decimals(table, Config.enforce_tick_sizes())
+  end
+
   # Simple cache put without TTL - entries persist until manually updated or cleared
   defp cache_put(key, value) do
     case Cachex.put(@cache, key, value) do
@@ -448,6 +456,7 @@ defmodule Hyperliquid.Cache do
       cache_put(:decimal_map, decimal_map)
       cache_put(:asset_to_sz_decimals, asset_to_sz_decimals)
       cache_put(:asset_to_price_decimals, asset_to_price_decimals)
+      push_sz_decimals(asset_to_sz_decimals)
       cache_put(:perps, Map.get(base_meta, "universe", []))
       cache_put(:spot_pairs, Map.get(spot_meta, "universe", []))
       cache_put(:tokens, Map.get(spot_meta, "tokens", []))
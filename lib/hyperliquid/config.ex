@@ -131,6 +131,126 @@ defmodule Hyperliquid.Config do
     end
   end
 
+  @doc """
+  Returns the encrypted keystore configuration, or nil when none is configured.
+
+  Used as the signing key when no plaintext `:private_key` is set. The keystore is a
+  standard Web3 Secret Storage (v3) JSON file, decrypted inside the signer NIF.
+
+  ## Configuration
+
+      config :hyperliquid,
+        keystore: %{path: "/etc/hyperliquid/key.json", password: System.get_env("HL_KEYSTORE_PASSWORD")}
+  """
+  def keystore do
+    case Application.get_env(:hyperliquid, :keystore, nil) do
+      nil -> Map.get(chain_cfg(), :keystore, nil)
+      keystore -> keystore
+    end
+  end
+
+  @doc """
+  Returns the external signing daemon configuration, or nil when none is configured.
+
+  When set, signing is forwarded to a daemon listening on a Unix domain socket and
+  the private key never enters the BEAM. See `native/signer/src/bin/signer_daemon.rs`
+  for the reference daemon.
+
+  ## Configuration
+
+      config :hyperliquid,
+        signer_socket: %{path: "/run/hl-signer.sock", address: "0x..."}
+  """
+  def signer_socket do
+    case Application.get_env(:hyperliquid, :signer_socket, nil) do
+      nil -> Map.get(chain_cfg(), :signer_socket, nil)
+      socket -> socket
+    end
+  end
+
+  @doc """
+  Returns the path of the signing policy file, or nil when none is configured.
+
+  The policy is loaded into the signer NIF at application start and checked before
+  every signature is produced. Files ending in `.json` are read as JSON, anything
+  else as TOML. See `native/signer/README.md` for the available rules.
+
+  ## Configuration
+
+      config :hyperliquid,
+        signing_policy: "/etc/hyperliquid/policy.toml"
+  """
+  def signing_policy do
+    case Application.get_env(:hyperliquid, :signing_policy, nil) do
+      nil -> Map.get(chain_cfg(), :signing_policy, nil)
+      path -> path
+    end
+  end
+
+  @doc """
+  Returns the path of the signing audit log, or nil when auditing is disabled.
+
+  When set, the signer NIF appends a hash-chained record of every signature to this
+  file, starting at application start. Verify it with `Hyperliquid.Signer.verify_audit_log/1`
+  or the `audit_log verify` binary shipped with the NIF crate.
+
+  ## Configuration
+
+      config :hyperliquid,
+        audit_log: "/var/log/hyperliquid/signatures.jsonl"
+  """
+  def audit_log do
+    case Application.get_env(:hyperliquid, :audit_log, nil) do
+      nil -> Map.get(chain_cfg(), :audit_log, nil)
+      path -> path
+    end
+  end
+
+  @doc """
+  Returns whether the signer refuses orders whose price or size breaks the asset's tick or lot
+  size. Defaults to false.
+
+  When enabled, `Hyperliquid.Cache` pushes its szDecimals table into the signer NIF and every
+  `order` / `batchModify` is validated before it is signed; orders for assets missing from the
+  table are refused.
+
+  ## Configuration
+
+      config :hyperliquid,
+        enforce_tick_sizes: true
+  """
+  def enforce_tick_sizes do
+    case Application.get_env(:hyperliquid, :enforce_tick_sizes, nil) do
+      nil -> Map.get(chain_cfg(), :enforce_tick_sizes, false)
+      value -> value
+    end
+  end
+
+  @doc """
+  Returns the network profile the signer uses for every network constant it signs over
+  (`hyperliquidChain`, `signatureChainId`, the L1 agent domain chain id and source).
+
+  Defaults to `:mainnet` or `:testnet` following `mainnet?/0`. A local devnet is described with a
+  map; fields it leaves out are taken from the built-in profile, and `:is_mainnet` defaults to
+  `mainnet?/0`.
+
+  ## Configuration
+
+      config :hyperliquid,
+        chain: :devnet,
+        is_mainnet: false,
+        chains: %{devnet: %{network_profile: %{name: "devnet", signature_chain_id: "0x539"}}}
+  """
+  def network_profile do
+    profile =
+      case Application.get_env(:hyperliquid, :network_profile, nil) do
+        nil -> Map.get(chain_cfg(), :network_profile, if(mainnet?(), do: :mainnet, else: :testnet))
+        value -> value
+      end
+
+    if is_map(profile), do: Map.put_new(profile, :is_mainnet, mainnet?()), else: profile
+  end
+
   @doc """
   Returns the bridge contract address, used for deposits.
   """
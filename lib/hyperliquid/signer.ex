@@ -20,21 +20,153 @@ defmodule Hyperliquid.Signer do
   # Standard NIF fallback pattern: :erlang.nif_error/1 raises when the NIF
   # binary hasn't been loaded. At runtime, these functions are replaced by
   # the Rust NIF implementations via @on_load.
+  def load_wallet(_private_key_hex),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def load_keystore(_path, _password),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def encrypt_keystore(_pk, _dir, _password, _name),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def new_keystore(_dir, _password, _name),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def load_mnemonic(_phrase, _passphrase, _derivation_path),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def derive_mnemonic_address(_phrase, _passphrase, _derivation_path),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def connect_signer(_socket_path, _expected_address, _timeout_ms),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def connect_typed_data_signer(_url, _address, _timeout_ms, _stringify),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def set_policy(_source, _format),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def clear_policy(),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def open_audit_log(_path),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def close_audit_log(),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_audit_log(_path),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   def compute_connection_id(_action_json, _nonce, _vault_address),
     do: :erlang.nif_error(:nif_not_loaded)
 
   def compute_connection_id_ex(_action_json, _nonce, _vault_address, _expires_after),
     do: :erlang.nif_error(:nif_not_loaded)
 
+  def explain_connection_id(_action_json, _nonce, _vault_address, _expires_after),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def decode_action_msgpack(_bytes),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   def sign_exchange_action(_pk, _action_json, _nonce, _is_mainnet, _vault_addr),
     do: :erlang.nif_error(:nif_not_loaded)
 
   def sign_exchange_action_ex(_pk, _action_json, _nonce, _is_mainnet, _vault_addr, _expires_after),
     do: :erlang.nif_error(:nif_not_loaded)
 
+  def float_to_wire(_value),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def decimal_to_wire(_value),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def format_price(_value, _sz_decimals, _is_spot, _mode),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def format_size(_value, _sz_decimals, _mode),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def set_sz_decimals(_table, _enforce),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def clear_sz_decimals(),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def set_network_profile(_profile),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def clear_network_profile(),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def network_profile(_is_mainnet),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def encode_action(_action),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def sign_action(_pk, _action, _nonce, _is_mainnet, _vault_addr, _expires_after),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def sign_user_signed_action(_pk, _action, _is_mainnet),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   def sign_l1_action(_pk, _connection_id, _is_mainnet),
     do: :erlang.nif_error(:nif_not_loaded)
 
+  def recover_l1_action_signer(_action_json, _nonce, _vault_addr, _expires_after, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def recover_usd_send(_dest, _amount, _time, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_usd_send(_address, _dest, _amount, _time, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def recover_withdraw3(_dest, _amount, _time, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_withdraw3(_address, _dest, _amount, _time, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def recover_spot_send(_dest, _token, _amount, _time, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_spot_send(_address, _dest, _token, _amount, _time, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def recover_approve_builder_fee(_builder, _max_fee_rate, _nonce, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_approve_builder_fee(_address, _builder, _max_fee_rate, _nonce, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def recover_approve_agent(_agent_addr, _agent_name, _nonce, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_approve_agent(_address, _agent_addr, _agent_name, _nonce, _is_mainnet, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def recover_multi_sig_action(_action_json, _nonce, _is_mainnet, _vault_addr, _expires_after, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_multi_sig_action(_address, _action_json, _nonce, _is_mainnet, _vault_addr, _expires_after, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def recover_typed_data(_domain_json, _types_json, _message_json, _primary_type, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_typed_data(_address, _domain_json, _types_json, _message_json, _primary_type, _r, _s, _v),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_explorer_tx(_tx_json, _is_mainnet),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_explorer_txs(_tx_jsons, _is_mainnet),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   def sign_usd_send(_pk, _dest, _amount, _time, _is_mainnet),
     do: :erlang.nif_error(:nif_not_loaded)
 
@@ -50,15 +182,54 @@ defmodule Hyperliquid.Signer do
   def sign_approve_agent(_pk, _agent_addr, _agent_name, _nonce, _is_mainnet),
     do: :erlang.nif_error(:nif_not_loaded)
 
+  def sign_usd_class_transfer(_pk, _amount, _to_perp, _nonce, _is_mainnet, _sub_account),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def generate_agent(_pk, _agent_name, _nonce, _is_mainnet),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   def sign_multi_sig_action_ex(_pk, _action_json, _nonce, _is_mainnet, _vault_addr, _expires_after),
     do: :erlang.nif_error(:nif_not_loaded)
 
+  def sign_multi_sig_l1_payload(_pk, _action, _multi_sig_user, _outer_signer, _nonce, _is_mainnet, _vault_addr, _expires_after),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def assemble_multi_sig_action(_multi_sig_user, _outer_signer, _action, _signatures, _is_mainnet),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def sign_multi_sig_user_signed_payload(_pk, _action, _multi_sig_user, _outer_signer, _is_mainnet),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def finalize_multi_sig_action(_pk, _multi_sig_user, _action, _signatures, _nonce, _is_mainnet, _vault_addr, _expires_after),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def new_multi_sig_bundle(_multi_sig_user, _outer_signer, _action, _nonce, _threshold, _signers, _is_mainnet, _vault_addr, _expires_after),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def multi_sig_bundle_to_compact(_bundle),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def multi_sig_bundle_to_json(_bundle),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def sign_multi_sig_bundle(_pk, _bundle),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def add_multi_sig_bundle_signature(_bundle, _signature),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def verify_multi_sig_bundle(_bundle),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  def finalize_multi_sig_bundle(_pk, _bundle),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   def sign_typed_data(_pk, _domain_json, _types_json, _message_json, _primary_type),
     do: :erlang.nif_error(:nif_not_loaded)
 
   def to_checksum_address(_addr),
     do: :erlang.nif_error(:nif_not_loaded)
 
-  def derive_address(_private_key_hex),
+  def derive_address(_pk),
     do: :erlang.nif_error(:nif_not_loaded)
 end
@@ -44,6 +44,22 @@ defmodule Hyperliquid.Utils do
     "0x" <> String.downcase(Integer.to_string(int, 16))
   end
 
+  @doc """
+  The `signatureChainId` user-signed actions carry, from the signer's network profile.
+  """
+  @spec signature_chain_id(boolean()) :: String.t()
+  def signature_chain_id(is_mainnet) do
+    Hyperliquid.Signer.network_profile(is_mainnet)["signature_chain_id"]
+  end
+
+  @doc """
+  The `hyperliquidChain` user-signed actions carry, from the signer's network profile.
+  """
+  @spec hyperliquid_chain(boolean()) :: String.t()
+  def hyperliquid_chain(is_mainnet) do
+    Hyperliquid.Signer.network_profile(is_mainnet)["hyperliquid_chain"]
+  end
+
   def numbers_to_strings(struct, fields) do
     Enum.reduce(fields, struct, fn field, acc ->
       value = Map.get(acc, field)
@@ -70,6 +86,19 @@ defmodule Hyperliquid.Utils do
     end
   end
 
+  @doc """
+  Format a price or size exactly as it is signed: at most 8 decimals, trailing zeros stripped
+  and `-0` written as `0` (see `Hyperliquid.Signer.float_to_wire/1`). Raises `ArgumentError`
+  for values that cannot be written with 8 decimals.
+  """
+  @spec to_wire(number() | String.t()) :: String.t()
+  def to_wire(value) when is_float(value), do: wire!(Hyperliquid.Signer.float_to_wire(value), value)
+  def to_wire(value) when is_integer(value), do: Integer.to_string(value)
+  def to_wire(value) when is_binary(value), do: wire!(Hyperliquid.Signer.decimal_to_wire(value), value)
+
+  defp wire!({:error, reason}, value), do: raise(ArgumentError, "#{inspect(value)}: #{reason}")
+  defp wire!(wire, _value), do: wire
+
   def make_cloid do
     :crypto.strong_rand_bytes(16)
     |> Base.encode16(case: :lower)
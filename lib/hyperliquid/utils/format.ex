@@ -9,19 +9,25 @@ defmodule Hyperliquid.Utils.Format do
   See: https://hyperliquid.gitbook.io/hyperliquid-docs/for-developers/api/tick-and-lot-size
   """
 
+  alias Hyperliquid.Signer
+
   @doc """
-  Format price according to Hyperliquid rules.
+  Format price according to Hyperliquid rules, truncating toward zero.
 
   Rules:
   - Maximum 5 significant figures
   - Maximum 6 (for perp) or 8 (for spot) - szDecimals decimal places
   - Integer prices are always allowed regardless of significant figures
 
+  The rules are applied with exact decimal arithmetic by the signer NIF. A price that
+  truncates to zero is returned as `"0"`; raises `ArgumentError` for negative prices.
+
   ## Parameters
     - `price`: Price as string, integer, or float
     - `sz_decimals`: Size decimals for the asset
     - `opts`: Options
       - `:perp` - true for perpetual (default), false for spot
+      - `:nonzero` - raise `ArgumentError` instead of returning `"0"` (default false)
 
   ## Examples
 
@@ -35,22 +41,7 @@ defmodule Hyperliquid.Utils.Format do
       "50000"
   """
   def format_price(price, sz_decimals, opts \\ []) do
-    is_perp = Keyword.get(opts, :perp, true)
-    price_str = to_string(price) |> String.trim()
-
-    # Integer prices are always allowed
-    if Regex.match?(~r/^-?\d+$/, price_str) do
-      trim_zeros(price_str)
-    else
-      # Apply decimal limit: max 6 (perp) or 8 (spot) - szDecimals
-      max_decimals = max(if(is_perp, do: 6, else: 8) - sz_decimals, 0)
-      price_str = to_fixed_truncate(price_str, max_decimals)
-
-      # Apply sig figs limit: max 5 significant figures
-      price_str = to_precision_truncate(price_str, 5)
-
-      price_str
-    end
+    price |> apply_price(sz_decimals, opts, :truncate) |> unwrap!() |> nonzero!(price, opts)
   end
 
   @doc """
@@ -59,9 +50,14 @@ defmodule Hyperliquid.Utils.Format do
   Rules:
   - Truncate decimal places to szDecimals
 
+  A size that truncates to zero is returned as `"0"`; raises `ArgumentError` for
+  negative sizes.
+
   ## Parameters
     - `size`: Size as string, integer, or float
     - `sz_decimals`: Size decimals for the asset
+    - `opts`: Options
+      - `:nonzero` - raise `ArgumentError` instead of returning `"0"` (default false)
 
   ## Examples
 
@@ -70,10 +66,46 @@ defmodule Hyperliquid.Utils.Format do
 
       iex> Format.format_size(0.001, 3)
       "0.001"
+
+      iex> Format.format_size("0.0001", 3)
+      "0"
   """
-  def format_size(size, sz_decimals) do
-    size_str = to_string(size) |> String.trim()
-    to_fixed_truncate(size_str, sz_decimals)
+  def format_size(size, sz_decimals, opts \\ []) do
+    size |> apply_size(sz_decimals, :truncate) |> unwrap!() |> nonzero!(size, opts)
+  end
+
+  @doc """
+  Check a price against Hyperliquid rules without changing it.
+
+  Returns `{:ok, wire_price}` when the price already fits, `{:error, reason}` otherwise.
+  Takes the same options as `format_price/3`.
+
+  ## Examples
+
+      iex> Format.validate_price("1.2345", 0)
+      {:ok, "1.2345"}
+
+      iex> Format.validate_price("1.23456", 0)
+      {:error, "parse error: price \\"1.23456\\" has more than 5 significant figures"}
+  """
+  def validate_price(price, sz_decimals, opts \\ []) do
+    case apply_price(price, sz_decimals, opts, :validate) do
+      {:error, _} = error -> error
+      wire -> {:ok, wire}
+    end
+  end
+
+  @doc """
+  Check a size against Hyperliquid rules without changing it.
+
+  Returns `{:ok, wire_size}` when the size has at most `sz_decimals` decimals,
+  `{:error, reason}` otherwise.
+  """
+  def validate_size(size, sz_decimals) do
+    case apply_size(size, sz_decimals, :validate) do
+      {:error, _} = error -> error
+      wire -> {:ok, wire}
+    end
   end
 
   @doc """
@@ -92,7 +124,32 @@ defmodule Hyperliquid.Utils.Format do
     max(max_decimals - sz_decimals, 0)
   end
 
-  # ===================== String Math Operations =====================
+  defp apply_price(price, sz_decimals, opts, mode) do
+    is_spot = not Keyword.get(opts, :perp, true)
+    Signer.format_price(to_decimal_string(price), sz_decimals, is_spot, mode)
+  end
+
+  defp apply_size(size, sz_decimals, mode) do
+    Signer.format_size(to_decimal_string(size), sz_decimals, mode)
+  end
+
+  defp to_decimal_string(value) when is_binary(value), do: String.trim(value)
+  defp to_decimal_string(value), do: to_string(value)
+
+  defp unwrap!({:error, reason}), do: raise(ArgumentError, reason)
+  defp unwrap!(wire), do: wire
+
+  defp nonzero!("0", value, opts) do
+    if Keyword.get(opts, :nonzero, false) do
+      raise ArgumentError, "#{inspect(value)} truncates to zero"
+    else
+      "0"
+    end
+  end
+
+  defp nonzero!(wire, _value, _opts), do: wire
+
+  # ===================== Deprecated =====================
 
   @doc """
   Truncate to a certain number of decimal places.
@@ -105,6 +162,7 @@ defmodule Hyperliquid.Utils.Format do
       iex> Format.to_fixed_truncate("100.999", 2)
       "100.99"
   """
+  @deprecated "Use format_size/3 or Hyperliquid.Signer.format_size/3 instead"
   def to_fixed_truncate(value, decimals) when decimals >= 0 do
     case String.split(value, ".") do
       [int] ->
@@ -130,6 +188,7 @@ defmodule Hyperliquid.Utils.Format do
       iex> Format.to_precision_truncate("0.00012345", 3)
       "0.000123"
   """
+  @deprecated "Use format_price/3 or Hyperliquid.Signer.format_price/4 instead"
   def to_precision_truncate(value, precision) when precision >= 1 do
     # Handle zero specially
     if Regex.match?(~r/^-?0+(\.0*)?$/, value) do
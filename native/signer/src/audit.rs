@@ -0,0 +1,205 @@
+// ===== Signing audit log =====
+//
+// Optional append-only record of every signature the NIF produces. Once `open_audit_log/1` has
+// been called, each signing function appends one JSON line and syncs it to disk before it returns
+// the signature; if the record cannot be written the signature is withheld.
+//
+//   {"seq":0,"time_ms":..,"action":"order","connection_id":"0x..","digest":"0x..","nonce":..,
+//    "vault_address":null,"signer":"0x..","signature":"0x..","prev":"0x00..00","hash":"0x.."}
+//
+// `digest` is the EIP-712 signing hash the signature is over; L1 actions also carry the
+// `connection_id` it was built from. `hash` is keccak256 of the record serialized without `hash`,
+// and `prev` is the previous record's `hash` (zero for the first record), so editing, inserting
+// or deleting a record breaks the chain. Truncating the tail is only detectable against a
+// previously noted head hash, which `verify_audit_log/1` returns along with the record count.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::{keccak256, Address, Signature as AlloySignature, B256};
+use rustler::{Encoder, Env, NifResult, Term};
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+rustler::atoms! { ok }
+
+static AUDIT_LOG: Mutex<Option<AuditLog>> = Mutex::new(None);
+
+// What the caller knows about a signature beyond the digest itself.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub action: Option<String>,
+    pub connection_id: Option<B256>,
+    pub nonce: Option<u64>,
+    pub vault_address: Option<Address>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub seq: u64,
+    pub time_ms: u64,
+    pub action: Option<String>,
+    pub connection_id: Option<B256>,
+    pub digest: B256,
+    pub nonce: Option<u64>,
+    pub vault_address: Option<Address>,
+    pub signer: Address,
+    pub signature: String,
+    pub prev: B256,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    #[serde(flatten)]
+    pub entry: Entry,
+    pub hash: B256,
+}
+
+impl Entry {
+    pub fn hash(&self) -> Result<B256, Error> {
+        let bytes = serde_json::to_vec(self).map_err(|e| Error::Audit(e.to_string()))?;
+        Ok(keccak256(bytes))
+    }
+}
+
+pub struct AuditLog { file: File, next_seq: u64, prev: B256 }
+
+impl AuditLog {
+    // Open `path` for appending, continuing the chain from its last record.
+    pub fn open(path: impl AsRef<Path>) -> Result<AuditLog, Error> {
+        let path = path.as_ref();
+        let (next_seq, prev) = match last_record(path)? {
+            Some(last) => (last.entry.seq + 1, last.hash),
+            None => (0, B256::ZERO),
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| Error::Audit(format!("open {}: {e}", path.display())))?;
+        Ok(AuditLog { file, next_seq, prev })
+    }
+
+    pub fn append(&mut self, ctx: Context, digest: B256, signer: Address, sig: &AlloySignature) -> Result<(), Error> {
+        let time_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+        let entry = Entry {
+            seq: self.next_seq,
+            time_ms,
+            action: ctx.action,
+            connection_id: ctx.connection_id,
+            digest,
+            nonce: ctx.nonce,
+            vault_address: ctx.vault_address,
+            signer,
+            signature: sig.to_string(),
+            prev: self.prev,
+        };
+        let hash = entry.hash()?;
+        let mut line = serde_json::to_string(&Record { entry, hash }).map_err(|e| Error::Audit(e.to_string()))?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes()).map_err(|e| Error::Audit(format!("write: {e}")))?;
+        self.next_seq += 1;
+        self.prev = hash;
+        // The record must be on disk before the signature leaves the NIF.
+        self.file.sync_data().map_err(|e| Error::Audit(format!("sync: {e}")))
+    }
+}
+
+fn last_record(path: &Path) -> Result<Option<Record>, Error> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::Audit(format!("open {}: {e}", path.display()))),
+    };
+    let mut last = None;
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| Error::Audit(format!("read: {e}")))?;
+        if !line.trim().is_empty() {
+            last = Some(line);
+        }
+    }
+    last.map(|line| {
+        serde_json::from_str(&line).map_err(|e| Error::Audit(format!("last record is unreadable, refusing to append: {e}")))
+    })
+    .transpose()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary { pub records: u64, pub head: B256 }
+
+// Check every record: canonical encoding, sequence, chain link, record hash and that the
+// signature recovers to the recorded signer over the recorded digest.
+pub fn verify(path: impl AsRef<Path>) -> Result<Summary, Error> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| Error::Audit(format!("open {}: {e}", path.display())))?;
+    let mut summary = Summary { records: 0, head: B256::ZERO };
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line_no = index + 1;
+        let fail = |msg: String| Error::Audit(format!("line {line_no}: {msg}"));
+        let line = line.map_err(|e| fail(e.to_string()))?;
+        let record: Record = serde_json::from_str(&line).map_err(|e| fail(format!("unreadable record: {e}")))?;
+        if serde_json::to_string(&record).map_err(|e| fail(e.to_string()))? != line {
+            return Err(fail("record is not in canonical form".to_string()));
+        }
+        let entry = &record.entry;
+        if entry.seq != summary.records {
+            return Err(fail(format!("expected seq {}, found {}", summary.records, entry.seq)));
+        }
+        if entry.prev != summary.head {
+            return Err(fail("prev does not match the previous record hash".to_string()));
+        }
+        if entry.hash()? != record.hash {
+            return Err(fail("record hash mismatch".to_string()));
+        }
+        let sig = AlloySignature::from_str(&entry.signature).map_err(|e| fail(format!("invalid signature: {e}")))?;
+        match sig.recover_address_from_prehash(&entry.digest) {
+            Ok(signer) if signer == entry.signer => {}
+            _ => return Err(fail("signature does not recover to signer".to_string())),
+        }
+        summary.records += 1;
+        summary.head = record.hash;
+    }
+    Ok(summary)
+}
+
+// Append a record for a signature that is about to be returned; a no-op when no log is open.
+pub(crate) fn append(ctx: Context, digest: B256, signer: Address, sig: &AlloySignature) -> Result<(), Error> {
+    let mut log = AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    match log.as_mut() {
+        Some(log) => log.append(ctx, digest, signer, sig),
+        None => Ok(()),
+    }
+}
+
+// Start recording every signature to `path`, continuing an existing log if present.
+#[rustler::nif(schedule = "DirtyIo")]
+fn open_audit_log(path: String) -> NifResult<rustler::Atom> {
+    let log = AuditLog::open(&path)?;
+    *AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(log);
+    Ok(ok())
+}
+
+#[rustler::nif]
+fn close_audit_log() -> rustler::Atom {
+    *AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    ok()
+}
+
+// Verify the chain in `path`; returns the record count and the hash of the last record.
+#[rustler::nif(schedule = "DirtyIo")]
+fn verify_audit_log<'a>(env: Env<'a>, path: String) -> NifResult<Term<'a>> {
+    let summary = verify(&path)?;
+    let mut map = rustler::types::map::map_new(env);
+    map = map
+        .map_put("records".encode(env), summary.records.encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    map = map
+        .map_put("head".encode(env), format!("{:#x}", summary.head).encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    Ok(map)
+}
@@ -0,0 +1,212 @@
+// ===== Signer backends =====
+//
+// Every signature in this crate is a secp256k1 signature over a 32-byte digest (the EIP-712
+// signing hash of an `Agent` or `HyperliquidTransaction:*` struct). A backend only has to sign
+// that digest, so keys can live in-process (`PrivateKeySigner`) or in a separate daemon reached
+// over a Unix domain socket (`SocketSigner`).
+//
+// Socket protocol: one JSON-RPC 2.0 request per line, one response per line.
+//
+//   -> {"jsonrpc":"2.0","id":1,"method":"hl_address","params":{}}
+//   <- {"jsonrpc":"2.0","id":1,"result":{"address":"0x..."}}
+//   -> {"jsonrpc":"2.0","id":2,"method":"hl_signHash","params":{"hash":"0x<32 bytes>"}}
+//   <- {"jsonrpc":"2.0","id":2,"result":{"signature":"0x<65 bytes r||s||v>"}}
+//
+// Errors use the standard `{"error":{"code":..,"message":..}}` member.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use alloy::primitives::{Address, Signature as AlloySignature, B256};
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use rustler::{NifResult, ResourceArc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+use crate::{parse_optional_address, Error, WalletResource};
+
+pub trait SignerBackend: Send + Sync + std::panic::RefUnwindSafe {
+    fn address(&self) -> Address;
+    fn sign_hash(&self, hash: &B256) -> Result<AlloySignature, Error>;
+    // Sign an EIP-712 payload whose signing hash is `digest`. `typed_data` builds the full
+    // eth_signTypedData_v4 document and is only called by backends that sign documents rather
+    // than digests; the default signs the digest directly.
+    fn sign_typed(&self, digest: &B256, _typed_data: &dyn Fn() -> JsonValue) -> Result<AlloySignature, Error> {
+        self.sign_hash(digest)
+    }
+    // In-process key, for operations that need the key itself (e.g. keystore export).
+    fn local_signer(&self) -> Option<&PrivateKeySigner> { None }
+}
+
+impl SignerBackend for PrivateKeySigner {
+    fn address(&self) -> Address { PrivateKeySigner::address(self) }
+    fn sign_hash(&self, hash: &B256) -> Result<AlloySignature, Error> {
+        self.sign_hash_sync(hash).map_err(|e| Error::SignatureFailure(e.to_string()))
+    }
+    fn local_signer(&self) -> Option<&PrivateKeySigner> { Some(self) }
+}
+
+pub const DEFAULT_SOCKET_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Longest daemon reply accepted; a signature response is a few hundred bytes.
+pub const MAX_SOCKET_REPLY: u64 = 64 * 1024;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcRequest { pub jsonrpc: String, pub id: u64, pub method: String, #[serde(default)] pub params: JsonValue }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcError { pub code: i64, pub message: String }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub result: Option<JsonValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub error: Option<RpcError>,
+}
+
+#[cfg(unix)]
+pub use socket::SocketSigner;
+
+// Unix domain sockets only exist on unix targets; elsewhere `connect_signer` reports an error.
+#[cfg(unix)]
+mod socket {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::os::unix::net::UnixStream;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+
+    // Client for an external signing daemon. Opens one connection per request so a restarted
+    // daemon is picked up transparently; every returned signature is checked to recover to the
+    // address the daemon reported at connect time.
+    pub struct SocketSigner { path: PathBuf, address: Address, timeout: Duration, next_id: AtomicU64 }
+
+    impl SocketSigner {
+        pub fn connect(path: impl AsRef<Path>, expected: Option<Address>, timeout: Duration) -> Result<Self, Error> {
+            let mut signer = SocketSigner { path: path.as_ref().to_path_buf(), address: Address::ZERO, timeout, next_id: AtomicU64::new(1) };
+            let result = signer.call("hl_address", json!({}))?;
+            let address = result
+                .get("address")
+                .and_then(JsonValue::as_str)
+                .ok_or_else(|| Error::Backend("hl_address: missing address".to_string()))
+                .and_then(|a| Address::from_str(a).map_err(|e| Error::Backend(format!("hl_address: invalid address: {e}"))))?;
+            if let Some(expected) = expected {
+                if expected != address {
+                    return Err(Error::Backend(format!("daemon address {address} does not match expected {expected}")));
+                }
+            }
+            signer.address = address;
+            Ok(signer)
+        }
+
+        fn call(&self, method: &str, params: JsonValue) -> Result<JsonValue, Error> {
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            let stream = UnixStream::connect(&self.path)
+                .map_err(|e| Error::Backend(format!("connect {}: {e}", self.path.display())))?;
+            stream.set_read_timeout(Some(self.timeout)).map_err(|e| Error::Backend(e.to_string()))?;
+            stream.set_write_timeout(Some(self.timeout)).map_err(|e| Error::Backend(e.to_string()))?;
+
+            let request = RpcRequest { jsonrpc: "2.0".to_string(), id, method: method.to_string(), params };
+            let mut line = serde_json::to_string(&request).map_err(|e| Error::JsonParse(e.to_string()))?;
+            line.push('\n');
+            (&stream).write_all(line.as_bytes()).map_err(|e| Error::Backend(format!("write: {e}")))?;
+
+            let mut reply = String::new();
+            BufReader::new((&stream).take(MAX_SOCKET_REPLY)).read_line(&mut reply).map_err(|e| Error::Backend(format!("read: {e}")))?;
+            if !reply.ends_with('\n') && reply.len() as u64 >= MAX_SOCKET_REPLY {
+                return Err(Error::Backend(format!("response exceeds {MAX_SOCKET_REPLY} bytes")));
+            }
+            let response: RpcResponse = serde_json::from_str(&reply)
+                .map_err(|e| Error::Backend(format!("invalid response: {e}")))?;
+            if response.id != id {
+                return Err(Error::Backend(format!("response id {} does not match request id {id}", response.id)));
+            }
+            match (response.result, response.error) {
+                (_, Some(err)) => Err(Error::Backend(format!("{method} failed ({}): {}", err.code, err.message))),
+                (Some(result), None) => Ok(result),
+                (None, None) => Err(Error::Backend(format!("{method}: empty response"))),
+            }
+        }
+    }
+
+    impl SignerBackend for SocketSigner {
+        fn address(&self) -> Address { self.address }
+        fn sign_hash(&self, hash: &B256) -> Result<AlloySignature, Error> {
+            let result = self.call("hl_signHash", json!({ "hash": format!("{hash:#x}") }))?;
+            let sig_hex = result
+                .get("signature")
+                .and_then(JsonValue::as_str)
+                .ok_or_else(|| Error::Backend("hl_signHash: missing signature".to_string()))?;
+            let sig = AlloySignature::from_str(sig_hex)
+                .map_err(|e| Error::Backend(format!("hl_signHash: invalid signature: {e}")))?;
+            let recovered = sig
+                .recover_address_from_prehash(hash)
+                .map_err(|e| Error::Backend(format!("hl_signHash: unrecoverable signature: {e}")))?;
+            if recovered != self.address {
+                return Err(Error::Backend(format!("hl_signHash: signature recovers to {recovered}, expected {}", self.address)));
+            }
+            Ok(sig)
+        }
+    }
+}
+
+// Serve a single JSON-RPC request line with the given key. Used by the reference daemon.
+pub fn handle_request(signer: &PrivateKeySigner, line: &str) -> RpcResponse {
+    let (id, outcome) = match serde_json::from_str::<RpcRequest>(line) {
+        Ok(req) => (req.id, dispatch(signer, &req)),
+        Err(e) => (0, Err(RpcError { code: -32700, message: format!("parse error: {e}") })),
+    };
+    let (result, error) = match outcome {
+        Ok(v) => (Some(v), None),
+        Err(e) => (None, Some(e)),
+    };
+    RpcResponse { jsonrpc: "2.0".to_string(), id, result, error }
+}
+
+fn dispatch(signer: &PrivateKeySigner, req: &RpcRequest) -> Result<JsonValue, RpcError> {
+    match req.method.as_str() {
+        "hl_address" => Ok(json!({ "address": signer.address().to_string() })),
+        "hl_signHash" => {
+            let hash = req
+                .params
+                .get("hash")
+                .and_then(JsonValue::as_str)
+                .and_then(|h| B256::from_str(h).ok())
+                .ok_or_else(|| RpcError { code: -32602, message: "params.hash must be a 32-byte hex string".to_string() })?;
+            let sig = signer
+                .sign_hash_sync(&hash)
+                .map_err(|e| RpcError { code: -32000, message: e.to_string() })?;
+            Ok(json!({ "signature": sig.to_string() }))
+        }
+        other => Err(RpcError { code: -32601, message: format!("method not found: {other}") }),
+    }
+}
+
+// Connect to a signing daemon listening on `socket_path` and return a wallet handle that
+// signs through it. `expected_address` guards against pointing at the wrong daemon.
+#[cfg(unix)]
+#[rustler::nif(schedule = "DirtyIo")]
+fn connect_signer(
+    socket_path: String,
+    expected_address: Option<String>,
+    timeout_ms: Option<u64>,
+) -> NifResult<ResourceArc<WalletResource>> {
+    let expected = parse_optional_address(expected_address)?;
+    let timeout = timeout_ms.map(Duration::from_millis).unwrap_or(DEFAULT_SOCKET_TIMEOUT);
+    let signer = SocketSigner::connect(&socket_path, expected, timeout)?;
+    Ok(ResourceArc::new(WalletResource::new(Box::new(signer))))
+}
+
+#[cfg(not(unix))]
+#[rustler::nif]
+fn connect_signer(
+    _socket_path: String,
+    _expected_address: Option<String>,
+    _timeout_ms: Option<u64>,
+) -> NifResult<ResourceArc<WalletResource>> {
+    Err(rustler::Error::Term(Box::new(
+        Error::Backend("unix domain sockets are not supported on this platform".to_string()).to_string(),
+    )))
+}
@@ -0,0 +1,31 @@
+// Offline checker for the signing audit log written by `Hyperliquid.Signer.open_audit_log/1`.
+//
+// Usage:
+//   audit_log verify FILE
+//
+// Exits 0 and prints the record count and head hash when the chain is intact; exits 1 with the
+// first broken line otherwise.
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [command, path] = args.as_slice() else {
+        eprintln!("usage: audit_log verify FILE");
+        return ExitCode::from(2);
+    };
+    if command != "verify" {
+        eprintln!("usage: audit_log verify FILE");
+        return ExitCode::from(2);
+    }
+    match signer_nif::audit::verify(path) {
+        Ok(summary) => {
+            println!("ok: {} records, head {:#x}", summary.records, summary.head);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("audit_log: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Offline tool for pending multi-sig bundles (see `src/multi_sig_bundle.rs`).
+//
+// Usage:
+//   multi_sig_bundle create --user ADDR --outer ADDR --signers ADDR,ADDR,.. --threshold K --nonce N
+//                           --action JSON|@FILE [--vault ADDR] [--expires-after MS] [--testnet] [--compact]
+//   multi_sig_bundle sign BUNDLE [--key-env VAR]
+//   multi_sig_bundle add BUNDLE R S V
+//   multi_sig_bundle verify BUNDLE
+//   multi_sig_bundle finalize BUNDLE [--key-env VAR]
+//
+// BUNDLE is a file holding the JSON or compact form, or `-` for stdin. `sign` and `add` print the
+// updated bundle in the form it was read in; `finalize` prints the /exchange request body. Keys
+// are read from the environment variable given by --key-env (default HL_SIGNER_KEY).
+
+use std::io::Read;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use alloy::primitives::{Address, Signature as AlloySignature, U256};
+use alloy::signers::local::PrivateKeySigner;
+use signer_nif::multi_sig_bundle::Bundle;
+
+const USAGE: &str = "usage:\n  \
+    multi_sig_bundle create --user ADDR --outer ADDR --signers ADDR,ADDR,.. --threshold K --nonce N\n                          \
+    --action JSON|@FILE [--vault ADDR] [--expires-after MS] [--testnet] [--compact]\n  \
+    multi_sig_bundle sign BUNDLE [--key-env VAR]\n  \
+    multi_sig_bundle add BUNDLE R S V\n  \
+    multi_sig_bundle verify BUNDLE\n  \
+    multi_sig_bundle finalize BUNDLE [--key-env VAR]\n\
+    \n\
+    BUNDLE is a file with the JSON or compact form, or - for stdin.";
+
+fn usage() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).map_err(|e| format!("stdin: {e}"))?;
+        Ok(text)
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))
+    }
+}
+
+fn address(value: &str) -> Result<Address, String> {
+    Address::from_str(value.trim()).map_err(|e| format!("invalid address {value:?}: {e}"))
+}
+
+fn number<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{flag} must be a number"))
+}
+
+fn load_signer(key_env: &str) -> Result<PrivateKeySigner, String> {
+    let key = zeroize::Zeroizing::new(std::env::var(key_env).map_err(|_| format!("{key_env} is not set"))?);
+    // Never echo the parse error: it can contain fragments of the key.
+    key.parse::<PrivateKeySigner>().map_err(|_| format!("{key_env} is not a valid private key"))
+}
+
+// `--key-env VAR` is the only option `sign` and `finalize` take.
+fn key_env(options: &[String]) -> Result<String, String> {
+    match options {
+        [] => Ok("HL_SIGNER_KEY".to_string()),
+        [flag, var] if flag == "--key-env" => Ok(var.clone()),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn create(options: &[String]) -> Result<String, String> {
+    let (mut user, mut outer, mut signers, mut threshold, mut nonce, mut action) = (None, None, None, None, None, None);
+    let (mut vault, mut expires_after, mut is_mainnet, mut compact) = (None, None, true, false);
+
+    let mut args = options.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--testnet" => is_mainnet = false,
+            "--compact" => compact = true,
+            _ => {
+                let value = args.next().ok_or_else(|| USAGE.to_string())?;
+                match flag.as_str() {
+                    "--user" => user = Some(address(value)?),
+                    "--outer" => outer = Some(address(value)?),
+                    "--signers" => signers = Some(value.split(',').map(address).collect::<Result<Vec<_>, _>>()?),
+                    "--threshold" => threshold = Some(number(flag, value)?),
+                    "--nonce" => nonce = Some(number(flag, value)?),
+                    "--action" => action = Some(value.strip_prefix('@').map_or_else(|| Ok(value.clone()), read_input)?),
+                    "--vault" => vault = Some(address(value)?),
+                    "--expires-after" => expires_after = Some(number(flag, value)?),
+                    _ => return Err(USAGE.to_string()),
+                }
+            }
+        }
+    }
+    let (Some(user), Some(outer), Some(signers), Some(threshold), Some(nonce), Some(action)) = (user, outer, signers, threshold, nonce, action)
+    else {
+        return Err(USAGE.to_string());
+    };
+    let action = serde_json::from_str(&action).map_err(|e| format!("invalid action JSON: {e}"))?;
+
+    let bundle = Bundle::new(is_mainnet, user, outer, action, nonce, vault, expires_after, threshold, signers).map_err(|e| e.to_string())?;
+    let encoded = if compact { bundle.to_compact() } else { bundle.to_json() };
+    encoded.map_err(|e| e.to_string())
+}
+
+fn sign(path: &str, options: &[String]) -> Result<String, String> {
+    let text = read_input(path)?;
+    let mut bundle = Bundle::parse(&text).map_err(|e| e.to_string())?;
+    let signer = load_signer(&key_env(options)?)?;
+    let address = bundle.sign(&signer).map_err(|e| e.to_string())?;
+    eprintln!("signed as {address}");
+    bundle.encode_like(&text).map_err(|e| e.to_string())
+}
+
+fn add(path: &str, options: &[String]) -> Result<String, String> {
+    let [r, s, v] = options else { return Err(USAGE.to_string()) };
+    let text = read_input(path)?;
+    let mut bundle = Bundle::parse(&text).map_err(|e| e.to_string())?;
+    let word = |name: &str, value: &str| U256::from_str(value).map_err(|e| format!("invalid {name}: {e}"));
+    let parity = match number::<u64>("V", v)? {
+        0 | 27 => false,
+        1 | 28 => true,
+        other => return Err(format!("invalid v: {other}")),
+    };
+    let address = bundle.add_signature(&AlloySignature::new(word("r", r)?, word("s", s)?, parity)).map_err(|e| e.to_string())?;
+    eprintln!("added signature from {address}");
+    bundle.encode_like(&text).map_err(|e| e.to_string())
+}
+
+// Prints one line per signature; exits 1 unless every signature verifies.
+fn verify(path: &str) -> ExitCode {
+    let bundle = match read_input(path).and_then(|text| Bundle::parse(&text).map_err(|e| e.to_string())) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!("multi_sig_bundle: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let verification = bundle.verify();
+    for check in &verification.checks {
+        match &check.error {
+            None => println!("ok      {}", check.signer),
+            Some(e) => println!("invalid {}: {e}", check.signer),
+        }
+    }
+    println!("{} of {} required signatures", verification.valid, bundle.threshold);
+    if verification.valid == verification.checks.len() { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+fn finalize(path: &str, options: &[String]) -> Result<String, String> {
+    let bundle = Bundle::parse(&read_input(path)?).map_err(|e| e.to_string())?;
+    let signer = load_signer(&key_env(options)?)?;
+    let finalized = bundle.finalize(&signer).map_err(|e| e.to_string())?;
+    let mut body = serde_json::json!({
+        "action": finalized.action,
+        "nonce": bundle.nonce,
+        "signature": {
+            "r": format!("0x{:064x}", finalized.signature.r()),
+            "s": format!("0x{:064x}", finalized.signature.s()),
+            "v": 27 + u8::from(finalized.signature.v()),
+        },
+        "expiresAfter": bundle.expires_after,
+    });
+    if let Some(vault) = bundle.vault_address {
+        body["vaultAddress"] = serde_json::Value::from(format!("{vault:#x}"));
+    }
+    Ok(body.to_string())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.as_slice() {
+        [command, options @ ..] if command == "create" => create(options),
+        [command, path, options @ ..] => match command.as_str() {
+            "sign" => sign(path, options),
+            "add" => add(path, options),
+            "verify" if options.is_empty() => return verify(path),
+            "finalize" => finalize(path, options),
+            _ => return usage(),
+        },
+        _ => return usage(),
+    };
+    match result {
+        Ok(out) => {
+            println!("{out}");
+            ExitCode::SUCCESS
+        }
+        Err(e) if e == USAGE => usage(),
+        Err(e) => {
+            eprintln!("multi_sig_bundle: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
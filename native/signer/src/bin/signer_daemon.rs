@@ -0,0 +1,124 @@
+// Reference signing daemon for the socket signer backend (`Hyperliquid.Signer.connect_signer/3`).
+//
+// Holds one key and answers the line-delimited JSON-RPC protocol described in `backend.rs`
+// over a Unix domain socket. Intended for local testing and as a template for real custody
+// daemons; it performs no policy checks of its own.
+//
+// Usage:
+//   signer_daemon --socket /run/hl-signer.sock [--key-env HL_SIGNER_KEY]
+//   signer_daemon --socket /run/hl-signer.sock --keystore key.json [--password-env HL_KEYSTORE_PASSWORD]
+
+use std::process::ExitCode;
+
+use alloy::signers::local::PrivateKeySigner;
+
+fn usage() -> ExitCode {
+    eprintln!(
+        "usage: signer_daemon --socket PATH [--key-env VAR | --keystore FILE [--password-env VAR]]\n\
+         \n\
+         The private key is read from the environment variable given by --key-env\n\
+         (default HL_SIGNER_KEY), or decrypted from a v3 keystore file whose password\n\
+         is read from --password-env (default HL_KEYSTORE_PASSWORD)."
+    );
+    ExitCode::from(2)
+}
+
+fn load_signer(key_env: &str, keystore: Option<&str>, password_env: &str) -> Result<PrivateKeySigner, String> {
+    match keystore {
+        Some(path) => {
+            let password = std::env::var(password_env).map_err(|_| format!("{password_env} is not set"))?;
+            PrivateKeySigner::decrypt_keystore(path, password).map_err(|e| format!("keystore error: {e}"))
+        }
+        None => {
+            let key = zeroize::Zeroizing::new(std::env::var(key_env).map_err(|_| format!("{key_env} is not set"))?);
+            // Never echo the parse error: it can contain fragments of the key.
+            key.parse::<PrivateKeySigner>().map_err(|_| format!("{key_env} is not a valid private key"))
+        }
+    }
+}
+
+#[cfg(unix)]
+fn serve(socket: &str, signer: PrivateKeySigner) -> std::io::Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::UnixListener;
+    use std::sync::Arc;
+
+    // A stale socket file from a previous run would make bind fail.
+    let _ = std::fs::remove_file(socket);
+    let listener = UnixListener::bind(socket)?;
+    // Anyone who can connect can have any digest signed, so only the daemon's own user may. The
+    // socket exists with the umask's mode until this runs; keep it in a 0700 directory to close
+    // that window on a shared host.
+    std::fs::set_permissions(socket, std::fs::Permissions::from_mode(0o600))?;
+    eprintln!("signer_daemon: serving {} on {socket}", signer.address());
+
+    let signer = Arc::new(signer);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("signer_daemon: accept failed: {e}");
+                continue;
+            }
+        };
+        let signer = Arc::clone(&signer);
+        std::thread::spawn(move || {
+            let mut writer = &stream;
+            for line in BufReader::new(&stream).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let response = signer_nif::backend::handle_request(&signer, &line);
+                let Ok(mut out) = serde_json::to_string(&response) else { break };
+                out.push('\n');
+                if writer.write_all(out.as_bytes()).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve(_socket: &str, _signer: PrivateKeySigner) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "unix domain sockets are not supported on this platform"))
+}
+
+fn main() -> ExitCode {
+    let mut socket = None;
+    let mut key_env = "HL_SIGNER_KEY".to_string();
+    let mut keystore = None;
+    let mut password_env = "HL_KEYSTORE_PASSWORD".to_string();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let Some(value) = args.next() else { return usage() };
+        match arg.as_str() {
+            "--socket" => socket = Some(value),
+            "--key-env" => key_env = value,
+            "--keystore" => keystore = Some(value),
+            "--password-env" => password_env = value,
+            _ => return usage(),
+        }
+    }
+    let Some(socket) = socket else { return usage() };
+
+    let signer = match load_signer(&key_env, keystore.as_deref(), &password_env) {
+        Ok(signer) => signer,
+        Err(e) => {
+            eprintln!("signer_daemon: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match serve(&socket, signer) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("signer_daemon: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,157 @@
+// ===== Canonical action encoding from Elixir terms =====
+//
+// Takes an action as an Elixir term instead of a JSON string, decodes it into the typed
+// `Actions` model and emits the canonical forms: msgpack (what the connection id is hashed
+// over) and JSON (the body to send). Field order therefore comes from the Rust structs, not from
+// how the caller built its map.
+//
+// Accepted terms: maps with atom or string keys, keyword lists, `Jason.OrderedObject`, lists,
+// strings, integers, floats, booleans and `nil`.
+//
+//   Signer.encode_action(%{type: "twapCancel", t: 7, a: 1})
+//   #=> %{"type" => "twapCancel", "json" => ~s({"type":"twapCancel","a":1,"t":7}), "msgpack" => <<...>>}
+
+use rustler::types::map::MapIterator;
+use rustler::types::tuple::get_tuple;
+use rustler::{Encoder, Env, ListIterator, NifResult, OwnedBinary, Term, TermType};
+use serde_json::{Map as JsonMap, Number, Value as JsonValue};
+
+use crate::{
+    action_type, audit, hash_action_with_exp, l1_action, parse_optional_address, policy, sign_l1_agent_action,
+    signature_to_map, ticks, Actions, Error, WalletRef,
+};
+
+rustler::atoms! { values }
+
+fn term_err(msg: impl Into<String>) -> Error {
+    Error::GenericParse(msg.into())
+}
+
+fn key_to_string(key: Term) -> Result<String, Error> {
+    match key.get_type() {
+        TermType::Atom => key.atom_to_string().map_err(|_| term_err("invalid atom key")),
+        TermType::Binary => key.decode::<String>().map_err(|_| term_err("map keys must be UTF-8 strings")),
+        _ => Err(term_err("map keys must be atoms or strings")),
+    }
+}
+
+// `{key, value}` with an atom key, i.e. one entry of a keyword list.
+fn keyword_pair(term: Term) -> Option<(Term, Term)> {
+    let pair = get_tuple(term).ok()?;
+    match pair.as_slice() {
+        [key, value] if key.is_atom() => Some((*key, *value)),
+        _ => None,
+    }
+}
+
+fn pairs_to_object<'a>(pairs: impl Iterator<Item = (Term<'a>, Term<'a>)>) -> Result<JsonValue, Error> {
+    let mut object = JsonMap::new();
+    for (key, value) in pairs {
+        object.insert(key_to_string(key)?, term_to_json(value)?);
+    }
+    Ok(JsonValue::Object(object))
+}
+
+pub fn term_to_json(term: Term) -> Result<JsonValue, Error> {
+    match term.get_type() {
+        TermType::Atom => match term.atom_to_string().map_err(|_| term_err("invalid atom"))?.as_str() {
+            "true" => Ok(JsonValue::Bool(true)),
+            "false" => Ok(JsonValue::Bool(false)),
+            "nil" => Ok(JsonValue::Null),
+            other => Ok(JsonValue::String(other.to_string())),
+        },
+        TermType::Binary => term.decode::<String>().map(JsonValue::String).map_err(|_| term_err("binaries must be UTF-8 strings")),
+        TermType::Integer => match (term.decode::<i64>(), term.decode::<u64>()) {
+            (Ok(n), _) => Ok(JsonValue::from(n)),
+            (_, Ok(n)) => Ok(JsonValue::from(n)),
+            _ => Err(term_err("integer does not fit in 64 bits")),
+        },
+        TermType::Float => {
+            let f = term.decode::<f64>().map_err(|_| term_err("invalid float"))?;
+            Number::from_f64(f).map(JsonValue::Number).ok_or_else(|| term_err("float must be finite"))
+        }
+        TermType::List => {
+            let items: Vec<Term> = term.decode::<ListIterator>().map_err(|_| term_err("improper list"))?.collect();
+            let pairs: Option<Vec<(Term, Term)>> = items.iter().map(|item| keyword_pair(*item)).collect();
+            match pairs {
+                Some(pairs) if !pairs.is_empty() => pairs_to_object(pairs.into_iter()),
+                _ => items.into_iter().map(term_to_json).collect::<Result<_, _>>().map(JsonValue::Array),
+            }
+        }
+        TermType::Map => {
+            if let Ok(module) = term.map_get(rustler::types::atom::__struct__()) {
+                let module = module.atom_to_string().unwrap_or_default();
+                if module != "Elixir.Jason.OrderedObject" {
+                    return Err(term_err(format!("unsupported struct {}", module.trim_start_matches("Elixir."))));
+                }
+                let values = term.map_get(values()).map_err(|_| term_err("invalid OrderedObject"))?;
+                let entries: Vec<Term> = values.decode::<ListIterator>().map_err(|_| term_err("invalid OrderedObject"))?.collect();
+                let pairs = entries
+                    .into_iter()
+                    .map(|entry| match get_tuple(entry).ok().as_deref() {
+                        Some([key, value]) => Ok((*key, *value)),
+                        _ => Err(term_err("invalid OrderedObject entry")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                return pairs_to_object(pairs.into_iter());
+            }
+            let iter = MapIterator::new(term).ok_or_else(|| term_err("invalid map"))?;
+            pairs_to_object(iter)
+        }
+        _ => Err(term_err("unsupported term; expected a map, keyword list, list, string, number, boolean or nil")),
+    }
+}
+
+pub fn decode_action(term: Term) -> Result<Actions, Error> {
+    serde_json::from_value(term_to_json(term)?).map_err(|e| Error::JsonParse(e.to_string()))
+}
+
+pub(crate) fn put<'a>(map: Term<'a>, key: &str, value: Term<'a>) -> NifResult<Term<'a>> {
+    map.map_put(key.encode(map.get_env()), value)
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))
+}
+
+// Decode an action term and return its wire `type`, canonical JSON and canonical msgpack.
+#[rustler::nif]
+fn encode_action<'a>(env: Env<'a>, action: Term<'a>) -> NifResult<Term<'a>> {
+    let action = decode_action(action)?;
+    let json = serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+    let bytes = rmp_serde::to_vec_named(&action).map_err(|e| Error::RmpParse(e.to_string()))?;
+    let mut msgpack = OwnedBinary::new(bytes.len()).ok_or_else(|| rustler::Error::Term(Box::new("failed to allocate binary")))?;
+    msgpack.as_mut_slice().copy_from_slice(&bytes);
+
+    let mut map = rustler::types::map::map_new(env);
+    map = put(map, "type", action_type(&action).encode(env))?;
+    map = put(map, "json", json.encode(env))?;
+    map = put(map, "msgpack", msgpack.release(env).encode(env))?;
+    Ok(map)
+}
+
+// Term form of `sign_exchange_action_ex/6`. The result also carries `action_json`, the canonical
+// body to send with the signature.
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_action<'a>(
+    env: Env<'a>,
+    wallet: WalletRef,
+    action: Term<'a>,
+    nonce: u64,
+    is_mainnet: bool,
+    vault_address: Option<String>,
+    expires_after: Option<u64>,
+) -> NifResult<Term<'a>> {
+    let action = decode_action(action)?;
+    let action = l1_action(action)?;
+    let vault = parse_optional_address(vault_address)?;
+
+    policy::enforce(policy::Request::action(&action, is_mainnet))?;
+    ticks::enforce(&action)?;
+
+    let cid = hash_action_with_exp(&action, nonce, vault, expires_after)?;
+    let json = serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+
+    let audit = audit::Context { action: Some(action_type(&action)), nonce: Some(nonce), vault_address: vault, ..Default::default() };
+    let sig = sign_l1_agent_action(&*wallet, cid, is_mainnet, audit)?;
+
+    let map = signature_to_map(env, sig, Some(cid))?;
+    put(map, "action_json", json.encode(env))
+}
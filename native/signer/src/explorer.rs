@@ -0,0 +1,183 @@
+// ===== Explorer transaction verification =====
+//
+// Checks a transaction as returned by the explorer (`txDetails` / the `txs` of `blockDetails`)
+// without contacting the API: decide whether it is an L1 action or a user-signed action,
+// rebuild the connection id or EIP-712 payload it was signed over and recover the signer.
+//
+// Accepted shape (optionally wrapped in `{"tx": ...}`):
+//
+//   {"user": "0x..", "action": {...}, "nonce": 123, "signature": {"r": "0x..", "s": "0x..", "v": 27},
+//    "vaultAddress": "0x.." | null, "expiresAfter": 123 | null}
+//
+// `signature` may also be a 65-byte hex string. User-signed actions are recognised by their
+// `signatureChainId` and `hyperliquidChain` fields and carry their own network; L1 actions
+// (including `multiSig` envelopes) are verified against the `is_mainnet` argument.
+//
+// L1 actions are usually signed by an agent wallet rather than the account itself, so a
+// recovered signer that differs from `user` is not by itself evidence of tampering.
+
+use std::str::FromStr;
+use std::thread;
+
+use alloy::primitives::{Address, Signature as AlloySignature, B256};
+use rustler::{Encoder, Env, NifResult, Term};
+use serde_json::Value as JsonValue;
+
+use crate::encode::put;
+use crate::recover::{parse_signature, recover_digest};
+use crate::user_signed::UserSigned;
+use crate::{hash_json_value_with_exp, l1_agent_payload, Eip712, Error, SendMultiSig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxKind { L1, UserSigned }
+
+impl TxKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TxKind::L1 => "l1",
+            TxKind::UserSigned => "user_signed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxVerification {
+    pub kind: TxKind,
+    pub action_type: String,
+    pub signer: Address,
+    pub user: Option<Address>,
+    pub matches_user: bool,
+    pub connection_id: Option<B256>,
+    pub digest: B256,
+}
+
+fn parse_err(msg: impl Into<String>) -> Error {
+    Error::GenericParse(msg.into())
+}
+
+fn str_field<'v>(obj: &'v JsonValue, key: &str) -> Result<&'v str, Error> {
+    obj.get(key).and_then(JsonValue::as_str).ok_or_else(|| parse_err(format!("missing string field {key}")))
+}
+
+fn u64_field(obj: &JsonValue, key: &str) -> Result<u64, Error> {
+    obj.get(key).and_then(JsonValue::as_u64).ok_or_else(|| parse_err(format!("missing integer field {key}")))
+}
+
+fn address_field(obj: &JsonValue, key: &str) -> Result<Address, Error> {
+    Address::from_str(str_field(obj, key)?).map_err(|e| parse_err(format!("invalid {key}: {e}")))
+}
+
+// Nullable fields appear both as absent and as explicit `null`.
+fn optional<'v>(obj: &'v JsonValue, key: &str) -> Option<&'v JsonValue> {
+    obj.get(key).filter(|v| !v.is_null())
+}
+
+pub(crate) fn chain_id(action: &JsonValue) -> Result<u64, Error> {
+    match action.get("signatureChainId") {
+        Some(JsonValue::String(s)) => {
+            let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+            u64::from_str_radix(hex, 16).map_err(|e| parse_err(format!("invalid signatureChainId: {e}")))
+        }
+        Some(JsonValue::Number(n)) => n.as_u64().ok_or_else(|| parse_err("invalid signatureChainId number")),
+        _ => Err(parse_err("missing signatureChainId")),
+    }
+}
+
+fn signature(tx: &JsonValue) -> Result<AlloySignature, Error> {
+    match tx.get("signature") {
+        Some(JsonValue::String(hex)) => {
+            AlloySignature::from_str(hex).map_err(|e| parse_err(format!("invalid signature: {e}")))
+        }
+        Some(sig @ JsonValue::Object(_)) => parse_signature(str_field(sig, "r")?, str_field(sig, "s")?, u64_field(sig, "v")?),
+        _ => Err(parse_err("missing signature")),
+    }
+}
+
+pub fn verify_tx(tx: &JsonValue, is_mainnet: bool) -> Result<TxVerification, Error> {
+    let tx = tx.get("tx").filter(|t| t.is_object()).unwrap_or(tx);
+    let action = tx.get("action").filter(|a| a.is_object()).ok_or_else(|| parse_err("missing action"))?;
+    let action_type = str_field(action, "type")?.to_string();
+    let user = optional(tx, "user").map(|_| address_field(tx, "user")).transpose()?;
+    let sig = signature(tx)?;
+
+    let is_user_signed = action.get("hyperliquidChain").is_some() && action.get("signatureChainId").is_some();
+    let (kind, connection_id, digest) = if is_user_signed {
+        (TxKind::UserSigned, None, UserSigned::from_action(action)?.eip712_signing_hash())
+    } else {
+        let nonce = u64_field(tx, "nonce")?;
+        let vault = optional(tx, "vaultAddress").map(|_| address_field(tx, "vaultAddress")).transpose()?;
+        let expires_after = optional(tx, "expiresAfter").map(|_| u64_field(tx, "expiresAfter")).transpose()?;
+        if action_type == "multiSig" {
+            // The outer signer signs SendMultiSig over the action hash computed without `type`.
+            let mut inner = action.clone();
+            if let Some(map) = inner.as_object_mut() {
+                map.shift_remove("type");
+            }
+            let multi_sig_action_hash = hash_json_value_with_exp(&inner, nonce, vault, expires_after)?;
+            let hyperliquid_chain = crate::network::profile(is_mainnet).hyperliquid_chain;
+            let payload = SendMultiSig { signature_chain_id: chain_id(action)?, hyperliquid_chain, multi_sig_action_hash, nonce };
+            (TxKind::L1, None, payload.eip712_signing_hash())
+        } else {
+            let cid = hash_json_value_with_exp(action, nonce, vault, expires_after)?;
+            (TxKind::L1, Some(cid), l1_agent_payload(cid, is_mainnet).eip712_signing_hash())
+        }
+    };
+
+    let signer = recover_digest(&sig, &digest)?;
+    Ok(TxVerification { kind, action_type, signer, user, matches_user: user == Some(signer), connection_id, digest })
+}
+
+impl TxVerification {
+    fn to_term<'a>(&self, env: Env<'a>) -> NifResult<Term<'a>> {
+        let mut map = rustler::types::map::map_new(env);
+        map = put(map, "kind", self.kind.as_str().encode(env))?;
+        map = put(map, "action_type", self.action_type.encode(env))?;
+        map = put(map, "signer", self.signer.to_string().encode(env))?;
+        map = put(map, "user", self.user.map(|u| u.to_string()).encode(env))?;
+        map = put(map, "matches_user", self.matches_user.encode(env))?;
+        map = put(map, "connection_id", self.connection_id.map(|c| format!("{c:#x}")).encode(env))?;
+        put(map, "digest", format!("{:#x}", self.digest).encode(env))
+    }
+}
+
+// Verify one explorer transaction (JSON string). Returns kind ("l1" / "user_signed"), action
+// type, recovered signer, user, whether they match, and the connection id / digest.
+#[rustler::nif]
+fn verify_explorer_tx<'a>(env: Env<'a>, tx_json: String, is_mainnet: bool) -> NifResult<Term<'a>> {
+    let tx: JsonValue = serde_json::from_str(&tx_json).map_err(|e| Error::JsonParse(e.to_string()))?;
+    verify_tx(&tx, is_mainnet)?.to_term(env)
+}
+
+// Verify many transactions at once (block backfills). Work is split across one thread per core;
+// each item gets its own `{:ok, map}` or `{:error, reason}` in input order.
+pub fn verify_txs(txs: &[String], is_mainnet: bool) -> Vec<Result<TxVerification, String>> {
+    let verify_one = |raw: &String| {
+        serde_json::from_str::<JsonValue>(raw)
+            .map_err(|e| Error::JsonParse(e.to_string()))
+            .and_then(|tx| verify_tx(&tx, is_mainnet))
+            .map_err(|e| e.to_string())
+    };
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let chunk_size = txs.len().div_ceil(workers).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = txs
+            .chunks(chunk_size)
+            .map(|chunk| (chunk.len(), scope.spawn(move || chunk.iter().map(verify_one).collect::<Vec<_>>())))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|(len, h)| h.join().unwrap_or_else(|_| vec![Err("verification panicked".to_string()); len]))
+            .collect()
+    })
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn verify_explorer_txs<'a>(env: Env<'a>, txs: Vec<String>, is_mainnet: bool) -> NifResult<Vec<Result<Term<'a>, String>>> {
+    verify_txs(&txs, is_mainnet)
+        .into_iter()
+        .map(|result| match result {
+            Ok(verification) => verification.to_term(env).map(Ok),
+            Err(e) => Ok(Err(e)),
+        })
+        .collect()
+}
@@ -0,0 +1,70 @@
+// ===== Web3 Secret Storage (v3 keystore) support =====
+//
+// Keystores are read from and written to disk by path. The decrypted key never leaves the NIF;
+// it is handed back to Elixir as a wallet handle. scrypt/pbkdf2 are deliberately slow, so these
+// NIFs run on the dirty CPU scheduler.
+
+use std::path::Path;
+
+use alloy::signers::local::PrivateKeySigner;
+use rustler::{Encoder, Env, NifResult, ResourceArc, Term};
+use zeroize::Zeroizing;
+
+use crate::{Error, SecretString, WalletRef, WalletResource};
+
+fn keystore_info<'a>(env: Env<'a>, signer: &PrivateKeySigner, dir: &str, file_name: &str) -> NifResult<Term<'a>> {
+    let path = Path::new(dir).join(file_name);
+    let mut map = rustler::types::map::map_new(env);
+    map = map
+        .map_put("address".encode(env), signer.address().to_string().encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    map = map
+        .map_put("path".encode(env), path.to_string_lossy().into_owned().encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    Ok(map)
+}
+
+// Decrypt a v3 keystore file (scrypt or pbkdf2 KDF) into a wallet handle.
+#[rustler::nif(schedule = "DirtyCpu")]
+fn load_keystore(path: String, password: SecretString) -> NifResult<ResourceArc<WalletResource>> {
+    let signer = PrivateKeySigner::decrypt_keystore(&path, password.expose())
+        .map_err(|e| Error::Keystore(e.to_string()))?;
+    Ok(ResourceArc::new(WalletResource::local(signer)))
+}
+
+// Encrypt an existing key (hex or handle) into a new keystore file inside `dir`.
+// The file is named `name` when given, otherwise after its random UUID.
+#[rustler::nif(schedule = "DirtyCpu")]
+fn encrypt_keystore<'a>(
+    env: Env<'a>,
+    wallet: WalletRef,
+    dir: String,
+    password: SecretString,
+    name: Option<String>,
+) -> NifResult<Term<'a>> {
+    let local = wallet.local_signer()?;
+    let key = Zeroizing::new(local.to_bytes().0);
+    let (signer, uuid) = PrivateKeySigner::encrypt_keystore(
+        &dir,
+        &mut rand::thread_rng(),
+        key.as_slice(),
+        password.expose(),
+        name.as_deref(),
+    )
+    .map_err(|e| Error::Keystore(e.to_string()))?;
+    keystore_info(env, &signer, &dir, name.as_deref().unwrap_or(&uuid))
+}
+
+// Generate a fresh random key and write it straight to a keystore file inside `dir`.
+// Only the address and file path are returned; the key itself stays encrypted on disk.
+#[rustler::nif(schedule = "DirtyCpu")]
+fn new_keystore<'a>(env: Env<'a>, dir: String, password: SecretString, name: Option<String>) -> NifResult<Term<'a>> {
+    let (signer, uuid) = PrivateKeySigner::new_keystore(
+        &dir,
+        &mut rand::thread_rng(),
+        password.expose(),
+        name.as_deref(),
+    )
+    .map_err(|e| Error::Keystore(e.to_string()))?;
+    keystore_info(env, &signer, &dir, name.as_deref().unwrap_or(&uuid))
+}
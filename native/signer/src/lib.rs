@@ -2,19 +2,42 @@ use std::str::FromStr;
 
 use alloy::dyn_abi::Eip712Domain;
 use alloy::primitives::{keccak256, Address, Signature as AlloySignature, B256};
-use alloy::signers::{local::PrivateKeySigner, SignerSync};
-use alloy::sol_types::{eip712_domain, SolStruct, SolValue};
-use rustler::{Env, NifResult, Term, Encoder};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol_types::{eip712_domain, SolStruct};
+use rustler::{Decoder, Encoder, Env, NifResult, ResourceArc, Term};
 use serde_json::Value as JsonValue;
 // For generic EIP-712 TypedData support
 use ethers_core::types::transaction::eip712::{TypedData as EthersTypedData, Eip712 as _};
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::backend::SignerBackend;
+use crate::typed_tx::hyperliquid_tx;
+
+pub mod audit;
+pub mod backend;
+mod encode;
+mod explorer;
+mod keystore;
+mod mnemonic;
+mod multi_sig;
+pub mod multi_sig_bundle;
+mod network;
+mod policy;
+mod preimage;
+mod recover;
+pub mod remote;
+mod ticks;
+mod typed_tx;
+mod user_signed;
+mod wire;
 
 // ===== Errors =====
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    // Fixed messages only: wallet errors must never carry key material back to Elixir.
     #[error("wallet error: {0}")]
-    Wallet(String),
+    Wallet(&'static str),
     #[error("parse error: {0}")]
     GenericParse(String),
     #[error("json parse error: {0}")]
@@ -23,85 +46,81 @@ pub enum Error {
     RmpParse(String),
     #[error("signature failure: {0}")]
     SignatureFailure(String),
+    #[error("keystore error: {0}")]
+    Keystore(String),
+    #[error("mnemonic error: {0}")]
+    Mnemonic(String),
+    #[error("signer backend error: {0}")]
+    Backend(String),
+    #[error("policy error: {0}")]
+    Policy(String),
+    #[error("audit error: {0}")]
+    Audit(String),
 }
 
-// EIP-712 for multi-sig send
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct SendMultiSig { pub signature_chain_id: u64, pub hyperliquid_chain: String, pub multi_sig_action_hash: B256, pub nonce: u64 }
-
-impl Eip712 for SendMultiSig {
-    fn domain(&self) -> Eip712Domain { tx_domain(self.signature_chain_id) }
-    fn struct_hash(&self) -> B256 {
-        let items = (
-            keccak256("HyperliquidTransaction:SendMultiSig(string hyperliquidChain,bytes32 multiSigActionHash,uint64 nonce)"),
-            keccak256(&self.hyperliquid_chain),
-            &self.multi_sig_action_hash,
-            &self.nonce,
-        );
-        keccak256(items.abi_encode())
+// NIFs hand crate errors back to Elixir as their message.
+impl From<Error> for rustler::Error {
+    fn from(e: Error) -> Self {
+        rustler::Error::Term(Box::new(e.to_string()))
     }
 }
 
-// Deterministic representation of MultiSig action for msgpack hashing
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-struct MsSignature { r: String, s: String, v: u8 }
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-struct MsPayloadAction { #[serde(rename = "type")] type_field: String, time: u64 }
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-struct MsPayload { multi_sig_user: String, outer_signer: String, action: MsPayloadAction }
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-struct MsAction { signature_chain_id: String, signatures: Vec<MsSignature>, payload: MsPayload }
-
-fn hash_ms_action_with_exp(
-    action: &MsAction,
-    timestamp: u64,
-    vault_address: Option<Address>,
-    expires_after: Option<u64>,
-) -> Result<B256, Error> {
-    let mut bytes = rmp_serde::to_vec_named(action).map_err(|e| Error::RmpParse(e.to_string()))?;
-    bytes.extend(timestamp.to_be_bytes());
-    if let Some(vault_address) = vault_address {
-        bytes.push(1);
-        bytes.extend(vault_address);
-    } else {
-        bytes.push(0);
-    }
-    if let Some(exp) = expires_after {
-        bytes.push(0);
-        bytes.extend(exp.to_be_bytes());
+// EIP-712 for multi-sig send
+hyperliquid_tx! {
+    SendMultiSig = "HyperliquidTransaction:SendMultiSig" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        multi_sig_action_hash: B256 => "multiSigActionHash",
+        nonce: u64 => "nonce",
     }
-    Ok(keccak256(bytes))
 }
 
 // New: Multi-sig variant that accepts arbitrary JSON action body (not constrained to Actions enum)
-#[rustler::nif]
+#[rustler::nif(schedule = "DirtyIo")]
 fn sign_multi_sig_action_ex<'a>(
     env: Env<'a>,
-    private_key_hex: String,
+    wallet: WalletRef,
     action_json: String,
     nonce: u64,
     is_mainnet: bool,
     vault_address: Option<String>,
     expires_after: Option<u64>,
 ) -> NifResult<Term<'a>> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let value: JsonValue = serde_json::from_str(&action_json).map_err(|e| Error::JsonParse(e.to_string()))?;
+    let vault = parse_optional_address(vault_address)?;
+
+    let sig = sign_multi_sig_value(&*wallet, &value, nonce, is_mainnet, vault, expires_after)?;
+    signature_to_map(env, sig, None)
+}
+
+// Outer `SendMultiSig` signature over an assembled multi-sig action, after the policy check.
+fn sign_multi_sig_value(
+    wallet: &dyn SignerBackend,
+    value: &JsonValue,
+    nonce: u64,
+    is_mainnet: bool,
+    vault: Option<Address>,
+    expires_after: Option<u64>,
+) -> NifResult<AlloySignature> {
+    policy::enforce(policy::Request::multi_sig(value, is_mainnet))?;
+    let payload = multi_sig_payload(value, nonce, is_mainnet, vault, expires_after)?;
 
-    let value: JsonValue = serde_json::from_str(&action_json)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-    let vault = parse_optional_address(vault_address)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let audit = audit::Context { action: Some("multiSig".to_string()), nonce: Some(nonce), vault_address: vault, ..Default::default() };
+    sign_typed_data(&payload, wallet, audit).map_err(rustler::Error::from)
+}
 
+// SendMultiSig payload for a multi-sig action: the signature chain id comes from the action's
+// `signatureChainId` (the network profile's when absent), the action hash from the action itself
+// with any `type` removed.
+fn multi_sig_payload(
+    value: &JsonValue,
+    nonce: u64,
+    is_mainnet: bool,
+    vault: Option<Address>,
+    expires_after: Option<u64>,
+) -> NifResult<SendMultiSig> {
+    let (default_chain_id, hyperliquid_chain) = chain(is_mainnet);
     // Parse signatureChainId (hex string like "0x66eee") from JSON map
-    let sig_chain_id = match &value {
+    let sig_chain_id = match value {
         JsonValue::Object(map) => {
             match map.get("signatureChainId") {
                 Some(JsonValue::String(s)) if s.starts_with("0x") || s.starts_with("0X") => {
@@ -109,39 +128,49 @@ fn sign_multi_sig_action_ex<'a>(
                         .map_err(|e| rustler::Error::Term(Box::new(format!("invalid signatureChainId: {}", e))))?
                 }
                 Some(JsonValue::Number(n)) => n.as_u64().ok_or_else(|| rustler::Error::Term(Box::new("invalid signatureChainId number".to_string())))?,
-                _ => return Err(rustler::Error::Term(Box::new("missing signatureChainId".to_string())))
+                None | Some(JsonValue::Null) => default_chain_id,
+                _ => return Err(rustler::Error::Term(Box::new("invalid signatureChainId".to_string())))
             }
         }
         _ => return Err(rustler::Error::Term(Box::new("action must be a JSON object".to_string())))
     };
 
-    // Compute multiSigActionHash over the full action object (no top-level type expected)
-    let ms_hash = hash_json_value_with_exp(&value, nonce, vault, expires_after)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-
-    // Build typed EIP-712 payload and sign
-    let hyperliquid_chain = if is_mainnet { "Mainnet".to_string() } else { "Testnet".to_string() };
-    let payload = SendMultiSig { signature_chain_id: sig_chain_id, hyperliquid_chain, multi_sig_action_hash: ms_hash, nonce };
-
-    let sig = sign_typed_data(&payload, &wallet)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    // Compute multiSigActionHash over the action without its `type`, as assembled actions carry it
+    let mut untagged = value.clone();
+    if let Some(map) = untagged.as_object_mut() {
+        map.shift_remove("type");
+    }
+    let ms_hash = hash_json_value_with_exp(&untagged, nonce, vault, expires_after)?;
 
-    signature_to_map(env, sig, None)
+    // Build typed EIP-712 payload
+    Ok(SendMultiSig { signature_chain_id: sig_chain_id, hyperliquid_chain, multi_sig_action_hash: ms_hash, nonce })
 }
 
 // Generic EIP-712 TypedData signer. Accepts JSON strings for domain/types/message and the primary type.
-#[rustler::nif]
+#[rustler::nif(schedule = "DirtyIo")]
 fn sign_typed_data<'a>(
     env: Env<'a>,
-    private_key_hex: String,
+    wallet: WalletRef,
     domain_json: String,
     types_json: String,
     message_json: String,
     primary_type: String,
 ) -> NifResult<Term<'a>> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    policy::enforce(policy::Request::opaque(&primary_type, None))?;
 
+    let (document, hash_b256) = typed_data_digest(domain_json, types_json, message_json, primary_type)?;
+
+    let sig = wallet.sign_typed(&hash_b256, &|| with_domain_type(&document))?;
+
+    let nonce = ["nonce", "time"].iter().find_map(|k| document["message"].get(k).and_then(JsonValue::as_u64));
+    let audit = audit::Context { action: document["primaryType"].as_str().map(str::to_string), nonce, ..Default::default() };
+    audit::append(audit, hash_b256, wallet.address(), &sig)?;
+
+    signature_to_map(env, sig, None)
+}
+
+// Parse the generic typed-data arguments into a v4 document and compute its signing hash.
+fn typed_data_digest(domain_json: String, types_json: String, message_json: String, primary_type: String) -> NifResult<(JsonValue, B256)> {
     let domain_val: JsonValue = serde_json::from_str(&domain_json)
         .map_err(|e| rustler::Error::Term(Box::new(format!("domain parse error: {}", e))))?;
     let types_val: JsonValue = serde_json::from_str(&types_json)
@@ -155,7 +184,8 @@ fn sign_typed_data<'a>(
     root.insert("message".to_string(), message_val);
     root.insert("primaryType".to_string(), JsonValue::String(primary_type));
 
-    let typed: EthersTypedData = serde_json::from_value(JsonValue::Object(root))
+    let document = JsonValue::Object(root);
+    let typed: EthersTypedData = serde_json::from_value(document.clone())
         .map_err(|e| rustler::Error::Term(Box::new(format!("typed data error: {}", e))))?;
 
     let digest = typed
@@ -165,17 +195,36 @@ fn sign_typed_data<'a>(
     // Convert the digest [u8;32] to B256 for alloy signer
     let hash_b256 = B256::from(digest);
 
-    let sig = wallet
-        .sign_hash_sync(&hash_b256)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    Ok((document, hash_b256))
+}
 
-    signature_to_map(env, sig, None)
+// Callers of the generic path usually omit `EIP712Domain` from `types`; wallets implementing
+// eth_signTypedData_v4 require it, so derive it from the domain fields that are present.
+fn with_domain_type(document: &JsonValue) -> JsonValue {
+    let mut document = document.clone();
+    let domain_fields: Vec<JsonValue> = [
+        ("name", "string"),
+        ("version", "string"),
+        ("chainId", "uint256"),
+        ("verifyingContract", "address"),
+        ("salt", "bytes32"),
+    ]
+    .iter()
+    .filter(|(name, _)| document["domain"].get(name).is_some())
+    .map(|(name, ty)| serde_json::json!({ "name": name, "type": ty }))
+    .collect();
+    if let Some(types) = document["types"].as_object_mut() {
+        types.entry("EIP712Domain").or_insert(JsonValue::Array(domain_fields));
+    }
+    document
 }
 
 // ===== EIP712 core trait =====
-trait Eip712 {
+pub trait Eip712 {
     fn domain(&self) -> Eip712Domain;
     fn struct_hash(&self) -> B256;
+    // Full eth_signTypedData_v4 document for this payload (for external typed-data signers).
+    fn typed_data(&self) -> JsonValue;
     fn eip712_signing_hash(&self) -> B256 {
         let mut digest_input = [0u8; 2 + 32 + 32];
         digest_input[0] = 0x19;
@@ -186,8 +235,41 @@ trait Eip712 {
     }
 }
 
+// Build an eth_signTypedData_v4 document: `types` (including EIP712Domain), `primaryType`,
+// `domain` and `message`. `fields` must list the struct members in type-string order.
+fn typed_data_document(domain: &Eip712Domain, primary_type: &str, fields: &[(&str, &str)], message: JsonValue) -> JsonValue {
+    let mut domain_fields = Vec::new();
+    let mut domain_json = serde_json::Map::new();
+    if let Some(name) = &domain.name {
+        domain_fields.push(serde_json::json!({ "name": "name", "type": "string" }));
+        domain_json.insert("name".to_string(), JsonValue::from(name.to_string()));
+    }
+    if let Some(version) = &domain.version {
+        domain_fields.push(serde_json::json!({ "name": "version", "type": "string" }));
+        domain_json.insert("version".to_string(), JsonValue::from(version.to_string()));
+    }
+    if let Some(chain_id) = domain.chain_id {
+        domain_fields.push(serde_json::json!({ "name": "chainId", "type": "uint256" }));
+        domain_json.insert("chainId".to_string(), JsonValue::from(chain_id.to::<u64>()));
+    }
+    if let Some(contract) = domain.verifying_contract {
+        domain_fields.push(serde_json::json!({ "name": "verifyingContract", "type": "address" }));
+        domain_json.insert("verifyingContract".to_string(), JsonValue::from(contract.to_string()));
+    }
+    let struct_fields: Vec<JsonValue> = fields
+        .iter()
+        .map(|(name, ty)| serde_json::json!({ "name": name, "type": ty }))
+        .collect();
+    serde_json::json!({
+        "types": { "EIP712Domain": domain_fields, primary_type: struct_fields },
+        "primaryType": primary_type,
+        "domain": domain_json,
+        "message": message,
+    })
+}
+
 // ===== L1 Agent typed struct (for L1 action signing) =====
-mod l1_agent {
+pub mod l1_agent {
     use super::*;
     alloy::sol! {
         #[derive(Debug)]
@@ -197,31 +279,54 @@ mod l1_agent {
         }
     }
 
-    impl super::Eip712 for Agent {
+    // `Agent` plus the domain chain id of the network profile it was built for.
+    #[derive(Debug, Clone)]
+    pub struct L1Agent {
+        pub source: String,
+        pub connection_id: B256,
+        pub chain_id: u64,
+    }
+
+    impl super::Eip712 for L1Agent {
         fn domain(&self) -> Eip712Domain {
             eip712_domain! {
                 name: "Exchange",
                 version: "1",
-                chain_id: 1337u64,
+                chain_id: self.chain_id,
                 verifying_contract: Address::ZERO,
             }
         }
-        fn struct_hash(&self) -> B256 { self.eip712_hash_struct() }
+        fn struct_hash(&self) -> B256 {
+            Agent { source: self.source.clone(), connectionId: self.connection_id }.eip712_hash_struct()
+        }
+        fn typed_data(&self) -> JsonValue {
+            typed_data_document(
+                &self.domain(),
+                "Agent",
+                &[("source", "string"), ("connectionId", "bytes32")],
+                serde_json::json!({ "source": self.source, "connectionId": format!("{:#x}", self.connection_id) }),
+            )
+        }
     }
+}
 
-    pub(super) use Agent as L1Agent;
+// Every typed signature goes through here so it is recorded in the audit log (when open)
+// before it is handed back.
+fn sign_typed_data<T: Eip712>(payload: &T, wallet: &dyn SignerBackend, audit: audit::Context) -> Result<AlloySignature, Error> {
+    let digest = payload.eip712_signing_hash();
+    let sig = wallet.sign_typed(&digest, &|| payload.typed_data())?;
+    audit::append(audit, digest, wallet.address(), &sig)?;
+    Ok(sig)
 }
 
-fn sign_typed_data<T: Eip712>(payload: &T, wallet: &PrivateKeySigner) -> Result<AlloySignature, Error> {
-    wallet
-        .sign_hash_sync(&payload.eip712_signing_hash())
-        .map_err(|e| Error::SignatureFailure(e.to_string()))
+fn l1_agent_payload(connection_id: B256, is_mainnet: bool) -> l1_agent::L1Agent {
+    let profile = network::profile(is_mainnet);
+    l1_agent::L1Agent { source: profile.agent_source, connection_id, chain_id: profile.agent_chain_id }
 }
 
-fn sign_l1_agent_action(wallet: &PrivateKeySigner, connection_id: B256, is_mainnet: bool) -> Result<AlloySignature, Error> {
-    let source = if is_mainnet { "a" } else { "b" }.to_string();
-    let payload = l1_agent::L1Agent { source, connectionId: connection_id };
-    sign_typed_data(&payload, wallet)
+fn sign_l1_agent_action(wallet: &dyn SignerBackend, connection_id: B256, is_mainnet: bool, audit: audit::Context) -> Result<AlloySignature, Error> {
+    let payload = l1_agent_payload(connection_id, is_mainnet);
+    sign_typed_data(&payload, wallet, audit::Context { connection_id: Some(connection_id), ..audit })
 }
 
 fn signature_to_map<'a>(env: Env<'a>, sig: AlloySignature, connection_id: Option<B256>) -> NifResult<Term<'a>> {
@@ -256,10 +361,120 @@ fn signature_to_map<'a>(env: Env<'a>, sig: AlloySignature, connection_id: Option
     Ok(map)
 }
 
+// Key parsing never echoes its input: errors carry fixed messages only, and the decoded bytes
+// are zeroized once the signer has been built.
 fn parse_wallet(priv_key_hex: &str) -> Result<PrivateKeySigner, Error> {
-    priv_key_hex
-        .parse::<PrivateKeySigner>()
-        .map_err(|e| Error::Wallet(e.to_string()))
+    let hex = priv_key_hex
+        .strip_prefix("0x")
+        .or_else(|| priv_key_hex.strip_prefix("0X"))
+        .unwrap_or(priv_key_hex);
+    let mut bytes = Zeroizing::new([0u8; 32]);
+    alloy::hex::decode_to_slice(hex, bytes.as_mut_slice())
+        .map_err(|_| Error::Wallet("invalid private key; expected 32 bytes of hex"))?;
+    PrivateKeySigner::from_slice(bytes.as_slice())
+        .map_err(|_| Error::Wallet("invalid private key; not a valid secp256k1 scalar"))
+}
+
+// ===== Secrets =====
+
+// String NIF argument holding secret material (private key hex, passphrase, mnemonic).
+// The Rust-side copy is wiped on drop and never shows up in Debug/Display output.
+pub struct SecretString(Zeroizing<String>);
+
+impl SecretString {
+    pub fn expose(&self) -> &str { self.0.as_str() }
+}
+
+impl std::fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str("SecretString(<redacted>)") }
+}
+
+impl std::fmt::Display for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str("<redacted>") }
+}
+
+impl<'a> Decoder<'a> for SecretString {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        Ok(SecretString(Zeroizing::new(term.decode::<String>()?)))
+    }
+}
+
+// ===== Wallet handles =====
+
+// Signer kept on the Rust side so the private key only crosses the NIF boundary once (or,
+// for external backends, never enters the BEAM at all).
+pub struct WalletResource { backend: Box<dyn SignerBackend> }
+
+impl WalletResource {
+    pub fn new(backend: Box<dyn SignerBackend>) -> Self { WalletResource { backend } }
+    pub fn local(signer: PrivateKeySigner) -> Self { Self::new(Box::new(signer)) }
+}
+
+#[rustler::resource_impl]
+impl rustler::Resource for WalletResource {}
+
+impl std::fmt::Debug for WalletResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WalletResource").field("address", &self.backend.address()).finish_non_exhaustive()
+    }
+}
+
+// Signing key argument accepted by every signing NIF: either a handle returned by
+// `load_wallet/1` (or another handle-producing NIF) or a hex private key parsed for this call only.
+// A handle may sign over a socket or HTTP round trip, so every NIF that signs with one runs on a
+// dirty IO scheduler.
+pub enum WalletRef {
+    Handle(ResourceArc<WalletResource>),
+    Owned(PrivateKeySigner),
+}
+
+impl WalletRef {
+    // In-process key behind this argument; external backends have none to hand out.
+    fn local_signer(&self) -> Result<&PrivateKeySigner, Error> {
+        let local = match self {
+            WalletRef::Handle(res) => res.backend.local_signer(),
+            WalletRef::Owned(signer) => Some(signer),
+        };
+        local.ok_or(Error::Wallet("operation requires an in-process private key"))
+    }
+}
+
+impl std::fmt::Debug for WalletRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WalletRef").field("address", &self.address()).finish_non_exhaustive()
+    }
+}
+
+impl std::ops::Deref for WalletRef {
+    type Target = dyn SignerBackend;
+    fn deref(&self) -> &(dyn SignerBackend + 'static) {
+        match self {
+            WalletRef::Handle(res) => res.backend.as_ref(),
+            WalletRef::Owned(signer) => signer,
+        }
+    }
+}
+
+impl<'a> Decoder<'a> for WalletRef {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        if let Ok(res) = term.decode::<ResourceArc<WalletResource>>() {
+            return Ok(WalletRef::Handle(res));
+        }
+        let hex: SecretString = term.decode()?;
+        parse_wallet(hex.expose())
+            .map(WalletRef::Owned)
+            .map_err(rustler::Error::from)
+    }
+}
+
+#[rustler::nif]
+fn load_wallet(private_key_hex: SecretString) -> NifResult<ResourceArc<WalletResource>> {
+    let signer = parse_wallet(private_key_hex.expose())?;
+    Ok(ResourceArc::new(WalletResource::local(signer)))
+}
+
+pub(crate) fn parse_address(what: &str, value: &str) -> Result<Address, Error> {
+    Address::from_str(value).map_err(|e| Error::GenericParse(format!("invalid {what}: {e}")))
 }
 
 fn parse_optional_address(addr_opt: Option<String>) -> Result<Option<Address>, Error> {
@@ -278,22 +493,7 @@ fn hash_action_with_exp(
     vault_address: Option<Address>,
     expires_after: Option<u64>,
 ) -> Result<B256, Error> {
-    let mut bytes = rmp_serde::to_vec_named(action).map_err(|e| Error::RmpParse(e.to_string()))?;
-    // nonce (timestamp) big-endian u64
-    bytes.extend(timestamp.to_be_bytes());
-    // vault flag + address bytes if present
-    if let Some(vault_address) = vault_address {
-        bytes.push(1);
-        bytes.extend(vault_address);
-    } else {
-        bytes.push(0);
-    }
-    // expiresAfter marker + value when present
-    if let Some(exp) = expires_after {
-        bytes.push(0);
-        bytes.extend(exp.to_be_bytes());
-    }
-    Ok(keccak256(bytes))
+    Ok(preimage::Preimage::new(action, timestamp, vault_address, expires_after)?.connection_id())
 }
 
 fn hash_action(action: &Actions, timestamp: u64, vault_address: Option<Address>) -> Result<B256, Error> {
@@ -306,130 +506,329 @@ fn hash_json_value_with_exp(
     vault_address: Option<Address>,
     expires_after: Option<u64>,
 ) -> Result<B256, Error> {
-    let mut bytes = rmp_serde::to_vec_named(value).map_err(|e| Error::RmpParse(e.to_string()))?;
-    // nonce (timestamp) big-endian u64
-    bytes.extend(timestamp.to_be_bytes());
-    // vault flag + address bytes if present
-    if let Some(vault_address) = vault_address {
-        bytes.push(1);
-        bytes.extend(vault_address);
-    } else {
-        bytes.push(0);
-    }
-    // expiresAfter marker + value when present
-    if let Some(exp) = expires_after {
-        bytes.push(0);
-        bytes.extend(exp.to_be_bytes());
-    }
-    Ok(keccak256(bytes))
+    Ok(preimage::Preimage::new(value, timestamp, vault_address, expires_after)?.connection_id())
 }
 
-// ===== Exchange action data (subset needed for signing) =====
+// ===== Exchange action data =====
+//
+// One struct per action, fields in wire order: `rmp_serde` hashes them in declaration order, so
+// this order is what the exchange sees regardless of how the caller built its JSON. Unknown
+// fields are rejected rather than silently dropped from the signed payload.
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Limit { pub tif: String }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Trigger { pub is_market: bool, pub trigger_px: String, pub tpsl: String }
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Trigger { pub is_market: bool, #[serde(deserialize_with = "wire::decimal")] pub trigger_px: String, pub tpsl: String }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum Order { Limit(Limit), Trigger(Trigger) }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct OrderRequest {
     #[serde(rename = "a", alias = "asset")] pub asset: u32,
     #[serde(rename = "b", alias = "isBuy")] pub is_buy: bool,
-    #[serde(rename = "p", alias = "limitPx")] pub limit_px: String,
-    #[serde(rename = "s", alias = "sz")] pub sz: String,
+    #[serde(rename = "p", alias = "limitPx", deserialize_with = "wire::decimal")] pub limit_px: String,
+    #[serde(rename = "s", alias = "sz", deserialize_with = "wire::decimal")] pub sz: String,
     #[serde(rename = "r", alias = "reduceOnly", default)] pub reduce_only: bool,
     #[serde(rename = "t", alias = "orderType")] pub order_type: Order,
     #[serde(rename = "c", alias = "cloid", skip_serializing_if = "Option::is_none")] pub cloid: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BuilderInfo { #[serde(rename = "b")] pub builder: String, #[serde(rename = "f")] pub fee: u64 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BulkOrder { pub orders: Vec<OrderRequest>, pub grouping: String, #[serde(default, skip_serializing_if = "Option::is_none")] pub builder: Option<BuilderInfo> }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct CancelRequest { #[serde(rename = "a", alias = "asset")] pub asset: u32, #[serde(rename = "o", alias = "oid")] pub oid: u64 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BulkCancel { pub cancels: Vec<CancelRequest> }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct CancelRequestCloid { pub asset: u32, pub cloid: String }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BulkCancelCloid { pub cancels: Vec<CancelRequestCloid> }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct ModifyRequest { #[serde(rename = "o", alias = "oid")] pub oid: u64, pub order: OrderRequest }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BulkModify { pub modifies: Vec<ModifyRequest> }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct UpdateLeverage { pub asset: u32, pub is_cross: bool, pub leverage: u32 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct UpdateIsolatedMargin { pub asset: u32, pub is_buy: bool, pub ntli: i64 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct ClassTransfer { pub usdc: u64, pub to_perp: bool }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SpotUser { pub class_transfer: ClassTransfer }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct VaultTransfer { pub vault_address: Address, pub is_deposit: bool, pub usd: u64 }
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct VaultTransfer { pub vault_address: String, pub is_deposit: bool, pub usd: u64 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SubAccountTransfer { pub sub_account_user: String, pub is_deposit: bool, pub usd: u64 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SubAccountSpotTransfer { pub sub_account_user: String, pub is_deposit: bool, pub token: String, pub amount: String }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct UsdClassTransfer { pub signature_chain_id: String, pub hyperliquid_chain: String, pub amount: String, pub to_perp: bool, pub nonce: u64 }
-
-#[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SetReferrer { pub code: String }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct EvmUserModify { pub using_big_blocks: bool }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct ScheduleCancel { #[serde(skip_serializing_if = "Option::is_none")] pub time: Option<u64> }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct ClaimRewards;
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Noop;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TwapRequest {
+    #[serde(rename = "a", alias = "asset")] pub asset: u32,
+    #[serde(rename = "b", alias = "isBuy")] pub is_buy: bool,
+    #[serde(rename = "s", alias = "sz", deserialize_with = "wire::decimal")] pub sz: String,
+    #[serde(rename = "r", alias = "reduceOnly", default)] pub reduce_only: bool,
+    #[serde(rename = "m", alias = "minutes")] pub minutes: u32,
+    #[serde(rename = "t", alias = "randomize", default)] pub randomize: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TwapOrder { pub twap: TwapRequest }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TwapCancel { #[serde(rename = "a", alias = "asset")] pub asset: u32, #[serde(rename = "t", alias = "twapId")] pub twap_id: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CreateVault { pub name: String, pub description: String, pub initial_usd: u64, pub nonce: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct VaultConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub portfolio_manager_cut_bps: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub allow_deposits: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct VaultModify { #[serde(default, skip_serializing_if = "Option::is_none")] pub vault_address: Option<String>, pub config: VaultConfig }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct VaultDistribute { pub vault_address: String }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CreateSubAccount { pub name: String }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SubAccountModify { #[serde(default, skip_serializing_if = "Option::is_none")] pub sub_account_user: Option<String>, pub name: String }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ConvertToMultiSigUser { pub authorized_users: Vec<String>, pub threshold: u32 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CSignerAction {
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub jail_self: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub unjail_self: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Delegation { pub validator: String, pub wei: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ChangeSigner { pub new_signer: String }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EditValidator {
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub commission_bps: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type")]
+#[serde(rename_all = "camelCase")]
+pub enum ValidatorAction {
+    Delegate(Delegation),
+    Undelegate(Delegation),
+    ChangeSigner(ChangeSigner),
+    EditValidator(EditValidator),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CValidatorAction { pub action: ValidatorAction }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AgentEnableDexAbstraction { pub enabled: bool }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AgentSetAbstraction { pub abstraction: String }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LinkStakingUser { pub link_to: String }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RegisterReferrer { pub code: String }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ReserveRequestWeight { pub weight: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ValidatorL1Stream { pub risk_free_rate: String }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SetDisplayName { pub display_name: String }
+
+// Wire form of user-signed actions. These are signed over their EIP-712 payloads (below), not
+// as L1 actions; they are modeled here so they can be validated and serialized like the rest.
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UsdSendAction { pub signature_chain_id: String, pub hyperliquid_chain: String, pub destination: String, pub amount: String, pub time: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SpotSendAction {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub destination: String,
+    pub token: String,
+    pub amount: String,
+    pub time: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Withdraw3Action { pub signature_chain_id: String, pub hyperliquid_chain: String, pub destination: String, pub amount: String, pub time: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UsdClassTransferAction { pub signature_chain_id: String, pub hyperliquid_chain: String, pub amount: String, pub to_perp: bool, pub nonce: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ApproveAgentAction {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub agent_address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub agent_name: Option<String>,
+    pub nonce: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ApproveBuilderFeeAction { pub signature_chain_id: String, pub hyperliquid_chain: String, pub builder: String, pub max_fee_rate: String, pub nonce: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SendAssetAction {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub destination: String,
+    pub source_dex: String,
+    pub destination_dex: String,
+    pub token: String,
+    pub amount: String,
+    pub from_sub_account: String,
+    pub nonce: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SendToEvmWithDataAction {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub token: String,
+    pub amount: String,
+    pub source_dex: String,
+    pub destination_recipient: String,
+    pub address_encoding: String,
+    pub destination_chain_id: u64,
+    pub gas_limit: u64,
+    pub data: String,
+    pub nonce: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CDepositAction { pub signature_chain_id: String, pub hyperliquid_chain: String, pub wei: u64, pub nonce: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CWithdrawAction { pub signature_chain_id: String, pub hyperliquid_chain: String, pub wei: u64, pub nonce: u64 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TokenDelegateAction {
+    pub signature_chain_id: String,
+    pub hyperliquid_chain: String,
+    pub validator: String,
+    pub is_undelegate: bool,
+    pub wei: u64,
+    pub nonce: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UserSetAbstractionAction { pub signature_chain_id: String, pub hyperliquid_chain: String, pub abstraction: String, pub nonce: u64 }
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "camelCase")]
@@ -444,11 +843,69 @@ pub enum Actions {
     VaultTransfer(VaultTransfer),
     SubAccountTransfer(SubAccountTransfer),
     SubAccountSpotTransfer(SubAccountSpotTransfer),
-    UsdClassTransfer(UsdClassTransfer),
+    UsdClassTransfer(UsdClassTransferAction),
     SetReferrer(SetReferrer),
     EvmUserModify(EvmUserModify),
     ScheduleCancel(ScheduleCancel),
     ClaimRewards(ClaimRewards),
+    Noop(Noop),
+    TwapOrder(TwapOrder),
+    TwapCancel(TwapCancel),
+    CreateVault(CreateVault),
+    VaultModify(VaultModify),
+    VaultDistribute(VaultDistribute),
+    CreateSubAccount(CreateSubAccount),
+    SubAccountModify(SubAccountModify),
+    ConvertToMultiSigUser(ConvertToMultiSigUser),
+    CSignerAction(CSignerAction),
+    CValidatorAction(CValidatorAction),
+    AgentEnableDexAbstraction(AgentEnableDexAbstraction),
+    AgentSetAbstraction(AgentSetAbstraction),
+    LinkStakingUser(LinkStakingUser),
+    RegisterReferrer(RegisterReferrer),
+    ReserveRequestWeight(ReserveRequestWeight),
+    ValidatorL1Stream(ValidatorL1Stream),
+    SetDisplayName(SetDisplayName),
+    UsdSend(UsdSendAction),
+    SpotSend(SpotSendAction),
+    Withdraw3(Withdraw3Action),
+    ApproveAgent(ApproveAgentAction),
+    ApproveBuilderFee(ApproveBuilderFeeAction),
+    SendAsset(SendAssetAction),
+    SendToEvmWithData(SendToEvmWithDataAction),
+    CDeposit(CDepositAction),
+    CWithdraw(CWithdrawAction),
+    TokenDelegate(TokenDelegateAction),
+    UserSetAbstraction(UserSetAbstractionAction),
+}
+
+impl Actions {
+    // User-signed actions carry their own EIP-712 signature and must not be signed as L1 actions.
+    pub fn is_user_signed(&self) -> bool {
+        matches!(
+            self,
+            Actions::UsdClassTransfer(_)
+                | Actions::UsdSend(_)
+                | Actions::SpotSend(_)
+                | Actions::Withdraw3(_)
+                | Actions::ApproveAgent(_)
+                | Actions::ApproveBuilderFee(_)
+                | Actions::SendAsset(_)
+                | Actions::SendToEvmWithData(_)
+                | Actions::CDeposit(_)
+                | Actions::CWithdraw(_)
+                | Actions::TokenDelegate(_)
+                | Actions::UserSetAbstraction(_)
+        )
+    }
+}
+
+// Wire `type` of an action, e.g. "order" or "batchModify".
+fn action_type(action: &Actions) -> String {
+    serde_json::to_value(action)
+        .ok()
+        .and_then(|v| v.get("type").and_then(JsonValue::as_str).map(str::to_string))
+        .unwrap_or_default()
 }
 
 // ===== EIP-712 typed payloads =====
@@ -462,105 +919,144 @@ fn tx_domain(chain_id: u64) -> Eip712Domain {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct UsdSend { pub signature_chain_id: u64, pub hyperliquid_chain: String, pub destination: String, pub amount: String, pub time: u64 }
+hyperliquid_tx! {
+    UsdSend = "HyperliquidTransaction:UsdSend" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        destination: String => "destination",
+        amount: String => "amount",
+        time: u64 => "time",
+    }
+}
 
-impl Eip712 for UsdSend {
-    fn domain(&self) -> Eip712Domain { tx_domain(self.signature_chain_id) }
-    fn struct_hash(&self) -> B256 {
-        let items = (
-            keccak256("HyperliquidTransaction:UsdSend(string hyperliquidChain,string destination,string amount,uint64 time)"),
-            keccak256(&self.hyperliquid_chain),
-            keccak256(&self.destination),
-            keccak256(&self.amount),
-            &self.time,
-        );
-        keccak256(items.abi_encode())
+hyperliquid_tx! {
+    Withdraw3 = "HyperliquidTransaction:Withdraw" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        destination: String => "destination",
+        amount: String => "amount",
+        time: u64 => "time",
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Withdraw3 { pub signature_chain_id: u64, pub hyperliquid_chain: String, pub destination: String, pub amount: String, pub time: u64 }
+hyperliquid_tx! {
+    SpotSend = "HyperliquidTransaction:SpotSend" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        destination: String => "destination",
+        token: String => "token",
+        amount: String => "amount",
+        time: u64 => "time",
+    }
+}
 
-impl Eip712 for Withdraw3 {
-    fn domain(&self) -> Eip712Domain { tx_domain(self.signature_chain_id) }
-    fn struct_hash(&self) -> B256 {
-        let items = (
-            keccak256("HyperliquidTransaction:Withdraw(string hyperliquidChain,string destination,string amount,uint64 time)"),
-            keccak256(&self.hyperliquid_chain),
-            keccak256(&self.destination),
-            keccak256(&self.amount),
-            &self.time,
-        );
-        keccak256(items.abi_encode())
+// `maxFeeRate` precedes `builder` in the type string, unlike in the action body.
+hyperliquid_tx! {
+    ApproveBuilderFee = "HyperliquidTransaction:ApproveBuilderFee" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        max_fee_rate: String => "maxFeeRate",
+        builder: Address => "builder",
+        nonce: u64 => "nonce",
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct SpotSend { pub signature_chain_id: u64, pub hyperliquid_chain: String, pub destination: String, pub token: String, pub amount: String, pub time: u64 }
+hyperliquid_tx! {
+    ApproveAgent = "HyperliquidTransaction:ApproveAgent" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        agent_address: Address => "agentAddress",
+        agent_name: Option<String> => "agentName",
+        nonce: u64 => "nonce",
+    }
+}
 
-impl Eip712 for SpotSend {
-    fn domain(&self) -> Eip712Domain { tx_domain(self.signature_chain_id) }
-    fn struct_hash(&self) -> B256 {
-        let items = (
-            keccak256("HyperliquidTransaction:SpotSend(string hyperliquidChain,string destination,string token,string amount,uint64 time)"),
-            keccak256(&self.hyperliquid_chain),
-            keccak256(&self.destination),
-            keccak256(&self.token),
-            keccak256(&self.amount),
-            &self.time,
-        );
-        keccak256(items.abi_encode())
+hyperliquid_tx! {
+    UsdClassTransfer = "HyperliquidTransaction:UsdClassTransfer" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        amount: String => "amount",
+        to_perp: bool => "toPerp",
+        nonce: u64 => "nonce",
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ApproveBuilderFee { pub signature_chain_id: u64, pub hyperliquid_chain: String, pub builder: Address, pub max_fee_rate: String, pub nonce: u64 }
+hyperliquid_tx! {
+    SendAsset = "HyperliquidTransaction:SendAsset" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        destination: String => "destination",
+        source_dex: String => "sourceDex",
+        destination_dex: String => "destinationDex",
+        token: String => "token",
+        amount: String => "amount",
+        from_sub_account: String => "fromSubAccount",
+        nonce: u64 => "nonce",
+    }
+}
 
-impl Eip712 for ApproveBuilderFee {
-    fn domain(&self) -> Eip712Domain { tx_domain(self.signature_chain_id) }
-    fn struct_hash(&self) -> B256 {
-        let items = (
-            keccak256("HyperliquidTransaction:ApproveBuilderFee(string hyperliquidChain,string maxFeeRate,address builder,uint64 nonce)"),
-            keccak256(&self.hyperliquid_chain),
-            keccak256(&self.max_fee_rate),
-            &self.builder,
-            &self.nonce,
-        );
-        keccak256(items.abi_encode())
+hyperliquid_tx! {
+    SendToEvmWithData = "HyperliquidTransaction:SendToEvmWithData" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        token: String => "token",
+        amount: String => "amount",
+        source_dex: String => "sourceDex",
+        destination_recipient: String => "destinationRecipient",
+        address_encoding: String => "addressEncoding",
+        destination_chain_id: u64 => "destinationChainId",
+        gas_limit: u64 => "gasLimit",
+        data: String => "data",
+        nonce: u64 => "nonce",
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ApproveAgent { pub signature_chain_id: u64, pub hyperliquid_chain: String, pub agent_address: Address, pub agent_name: Option<String>, pub nonce: u64 }
+hyperliquid_tx! {
+    CDeposit = "HyperliquidTransaction:CDeposit" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        wei: u64 => "wei",
+        nonce: u64 => "nonce",
+    }
+}
+
+hyperliquid_tx! {
+    CWithdraw = "HyperliquidTransaction:CWithdraw" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        wei: u64 => "wei",
+        nonce: u64 => "nonce",
+    }
+}
+
+hyperliquid_tx! {
+    TokenDelegate = "HyperliquidTransaction:TokenDelegate" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        validator: Address => "validator",
+        wei: u64 => "wei",
+        is_undelegate: bool => "isUndelegate",
+        nonce: u64 => "nonce",
+    }
+}
 
-impl Eip712 for ApproveAgent {
-    fn domain(&self) -> Eip712Domain { tx_domain(self.signature_chain_id) }
-    fn struct_hash(&self) -> B256 {
-        let items = (
-            keccak256("HyperliquidTransaction:ApproveAgent(string hyperliquidChain,address agentAddress,string agentName,uint64 nonce)"),
-            keccak256(&self.hyperliquid_chain),
-            &self.agent_address,
-            keccak256(self.agent_name.as_deref().unwrap_or("")),
-            &self.nonce,
-        );
-        keccak256(items.abi_encode())
+hyperliquid_tx! {
+    UserSetAbstraction = "HyperliquidTransaction:UserSetAbstraction" {
+        hyperliquid_chain: String => "hyperliquidChain",
+        abstraction: String => "abstraction",
+        nonce: u64 => "nonce",
     }
 }
 
+// Parse an action for L1 hashing: unknown types and fields are rejected, as are user-signed
+// actions, which the exchange only accepts with their EIP-712 signature.
+fn parse_l1_action(action_json: &str) -> NifResult<Actions> {
+    let action: Actions = serde_json::from_str(action_json).map_err(|e| Error::JsonParse(e.to_string()))?;
+    l1_action(action)
+}
+
+fn l1_action(action: Actions) -> NifResult<Actions> {
+    if action.is_user_signed() {
+        let msg = format!("{} is a user-signed action and cannot be signed as an L1 action", action_type(&action));
+        return Err(rustler::Error::Term(Box::new(msg)));
+    }
+    Ok(action)
+}
+
 #[rustler::nif]
 fn compute_connection_id(action_json: String, nonce: u64, vault_address: Option<String>) -> NifResult<String> {
-    let action: Actions = serde_json::from_str(&action_json)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-    let vault = parse_optional_address(vault_address)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-    let cid = hash_action(&action, nonce, vault)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let action = parse_l1_action(&action_json)?;
+    let vault = parse_optional_address(vault_address)?;
+    let cid = hash_action(&action, nonce, vault)?;
     Ok(format!("{:#x}", cid))
 }
 
@@ -573,140 +1069,204 @@ fn compute_connection_id_ex(
     expires_after: Option<u64>,
 ) -> NifResult<String> {
     // Use generic JSON hashing that works with any action type
-    let value: JsonValue = serde_json::from_str(&action_json)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-    let vault = parse_optional_address(vault_address)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-    let cid = hash_json_value_with_exp(&value, nonce, vault, expires_after)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let value: JsonValue = serde_json::from_str(&action_json).map_err(|e| Error::JsonParse(e.to_string()))?;
+    let vault = parse_optional_address(vault_address)?;
+    let cid = hash_json_value_with_exp(&value, nonce, vault, expires_after)?;
     Ok(format!("{:#x}", cid))
 }
 
-#[rustler::nif]
-fn sign_exchange_action<'a>(env: Env<'a>, private_key_hex: String, action_json: String, nonce: u64, is_mainnet: bool, vault_address: Option<String>) -> NifResult<Term<'a>> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-    let action: Actions = serde_json::from_str(&action_json)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-    let vault = parse_optional_address(vault_address)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+// Prices and sizes are normalized to wire form before hashing, so the result also carries
+// `action_json`, the canonical action that was signed and must be submitted.
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_exchange_action<'a>(env: Env<'a>, wallet: WalletRef, action_json: String, nonce: u64, is_mainnet: bool, vault_address: Option<String>) -> NifResult<Term<'a>> {
+    let action = parse_l1_action(&action_json)?;
+    let vault = parse_optional_address(vault_address)?;
 
-    let cid = hash_action(&action, nonce, vault)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    policy::enforce(policy::Request::action(&action, is_mainnet))?;
+    ticks::enforce(&action)?;
 
-    let sig = sign_l1_agent_action(&wallet, cid, is_mainnet)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let cid = hash_action(&action, nonce, vault)?;
 
-    signature_to_map(env, sig, Some(cid))
+    let json = serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+
+    let audit = audit::Context { action: Some(action_type(&action)), nonce: Some(nonce), vault_address: vault, ..Default::default() };
+    let sig = sign_l1_agent_action(&*wallet, cid, is_mainnet, audit)?;
+
+    let map = signature_to_map(env, sig, Some(cid))?;
+    encode::put(map, "action_json", json.encode(env))
 }
 
 // New: expiresAfter-aware variant
-#[rustler::nif]
+#[rustler::nif(schedule = "DirtyIo")]
 fn sign_exchange_action_ex<'a>(
     env: Env<'a>,
-    private_key_hex: String,
+    wallet: WalletRef,
     action_json: String,
     nonce: u64,
     is_mainnet: bool,
     vault_address: Option<String>,
     expires_after: Option<u64>,
 ) -> NifResult<Term<'a>> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-    let action: Actions = serde_json::from_str(&action_json)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
-    let vault = parse_optional_address(vault_address)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let action = parse_l1_action(&action_json)?;
+    let vault = parse_optional_address(vault_address)?;
 
-    let cid = hash_action_with_exp(&action, nonce, vault, expires_after)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    policy::enforce(policy::Request::action(&action, is_mainnet))?;
+    ticks::enforce(&action)?;
 
-    let sig = sign_l1_agent_action(&wallet, cid, is_mainnet)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let cid = hash_action_with_exp(&action, nonce, vault, expires_after)?;
 
-    signature_to_map(env, sig, Some(cid))
+    let json = serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+
+    let audit = audit::Context { action: Some(action_type(&action)), nonce: Some(nonce), vault_address: vault, ..Default::default() };
+    let sig = sign_l1_agent_action(&*wallet, cid, is_mainnet, audit)?;
+
+    let map = signature_to_map(env, sig, Some(cid))?;
+    encode::put(map, "action_json", json.encode(env))
 }
 
+// `signatureChainId` and `hyperliquidChain` of user-signed payloads, from the network profile.
 fn chain(is_mainnet: bool) -> (u64, String) {
-    // Hyperliquid uses chainId 42161 (Arbitrum One) for BOTH mainnet and testnet.
-    // The network distinction is conveyed via the hyperliquidChain field.
-    let chain_id = 42161u64;
-    let hyperliquid_chain = if is_mainnet { "Mainnet" } else { "Testnet" }.to_string();
-    (chain_id, hyperliquid_chain)
+    let profile = network::profile(is_mainnet);
+    (profile.signature_chain_id, profile.hyperliquid_chain)
 }
 
-#[rustler::nif]
-fn sign_usd_send<'a>(env: Env<'a>, private_key_hex: String, destination: String, amount: String, time: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_usd_send<'a>(env: Env<'a>, wallet: WalletRef, destination: String, amount: String, time: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
+    policy::enforce(policy::Request::transfer("usdSend", &destination, &amount, is_mainnet))?;
     let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
     let payload = UsdSend { signature_chain_id, hyperliquid_chain, destination, amount, time };
-    let sig = sign_typed_data(&payload, &wallet)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let audit = audit::Context { action: Some("usdSend".to_string()), nonce: Some(time), ..Default::default() };
+    let sig = sign_typed_data(&payload, &*wallet, audit)?;
     signature_to_map(env, sig, None)
 }
 
-#[rustler::nif]
-fn sign_withdraw3<'a>(env: Env<'a>, private_key_hex: String, destination: String, amount: String, time: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_withdraw3<'a>(env: Env<'a>, wallet: WalletRef, destination: String, amount: String, time: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
+    policy::enforce(policy::Request::transfer("withdraw3", &destination, &amount, is_mainnet))?;
     let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
     let payload = Withdraw3 { signature_chain_id, hyperliquid_chain, destination, amount, time };
-    let sig = sign_typed_data(&payload, &wallet)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let audit = audit::Context { action: Some("withdraw3".to_string()), nonce: Some(time), ..Default::default() };
+    let sig = sign_typed_data(&payload, &*wallet, audit)?;
     signature_to_map(env, sig, None)
 }
 
-#[rustler::nif]
-fn sign_spot_send<'a>(env: Env<'a>, private_key_hex: String, destination: String, token: String, amount: String, time: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_spot_send<'a>(env: Env<'a>, wallet: WalletRef, destination: String, token: String, amount: String, time: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
+    policy::enforce(policy::Request::transfer("spotSend", &destination, &amount, is_mainnet))?;
     let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
     let payload = SpotSend { signature_chain_id, hyperliquid_chain, destination, token, amount, time };
-    let sig = sign_typed_data(&payload, &wallet)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let audit = audit::Context { action: Some("spotSend".to_string()), nonce: Some(time), ..Default::default() };
+    let sig = sign_typed_data(&payload, &*wallet, audit)?;
     signature_to_map(env, sig, None)
 }
 
-#[rustler::nif]
-fn sign_approve_builder_fee<'a>(env: Env<'a>, private_key_hex: String, builder: String, max_fee_rate: String, nonce: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_approve_builder_fee<'a>(env: Env<'a>, wallet: WalletRef, builder: String, max_fee_rate: String, nonce: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
+    policy::enforce(policy::Request::user_action("approveBuilderFee", is_mainnet))?;
     let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
-    let builder_addr = Address::from_str(&builder)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let builder_addr = parse_address("builder", &builder)?;
     let payload = ApproveBuilderFee { signature_chain_id, hyperliquid_chain, builder: builder_addr, max_fee_rate, nonce };
-    let sig = sign_typed_data(&payload, &wallet)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let audit = audit::Context { action: Some("approveBuilderFee".to_string()), nonce: Some(nonce), ..Default::default() };
+    let sig = sign_typed_data(&payload, &*wallet, audit)?;
     signature_to_map(env, sig, None)
 }
 
-#[rustler::nif]
-fn sign_approve_agent<'a>(env: Env<'a>, private_key_hex: String, agent_address: String, agent_name: Option<String>, nonce: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_approve_agent<'a>(env: Env<'a>, wallet: WalletRef, agent_address: String, agent_name: Option<String>, nonce: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
+    policy::enforce(policy::Request::user_action("approveAgent", is_mainnet))?;
     let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
-    let agent_addr = Address::from_str(&agent_address)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let agent_addr = parse_address("agent_address", &agent_address)?;
     let payload = ApproveAgent { signature_chain_id, hyperliquid_chain, agent_address: agent_addr, agent_name, nonce };
-    let sig = sign_typed_data(&payload, &wallet)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+    let audit = audit::Context { action: Some("approveAgent".to_string()), nonce: Some(nonce), ..Default::default() };
+    let sig = sign_typed_data(&payload, &*wallet, audit)?;
     signature_to_map(env, sig, None)
 }
 
+// Transfer USDC between the spot and perp balances. With `sub_account` the transfer is made on
+// behalf of that sub-account, which the exchange expects as "<amount> subaccount:<address>" in
+// the signed amount. Returns the signature and the action JSON to submit.
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_usd_class_transfer<'a>(env: Env<'a>, wallet: WalletRef, amount: String, to_perp: bool, nonce: u64, is_mainnet: bool, sub_account: Option<String>) -> NifResult<Term<'a>> {
+    policy::enforce(policy::Request::class_transfer(&amount, is_mainnet))?;
+    let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
+    let amount = match sub_account {
+        Some(address) => {
+            Address::from_str(&address).map_err(|e| rustler::Error::Term(Box::new(format!("invalid sub_account: {e}"))))?;
+            format!("{amount} subaccount:{address}")
+        }
+        None => amount,
+    };
+    let action = Actions::UsdClassTransfer(UsdClassTransferAction {
+        signature_chain_id: format!("{signature_chain_id:#x}"),
+        hyperliquid_chain: hyperliquid_chain.clone(),
+        amount: amount.clone(),
+        to_perp,
+        nonce,
+    });
+    let action_json = serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+
+    let payload = UsdClassTransfer { signature_chain_id, hyperliquid_chain, amount, to_perp, nonce };
+    let audit = audit::Context { action: Some("usdClassTransfer".to_string()), nonce: Some(nonce), ..Default::default() };
+    let sig = sign_typed_data(&payload, &*wallet, audit)?;
+
+    let map = signature_to_map(env, sig, None)?;
+    map.map_put("action_json".encode(env), action_json.encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))
+}
+
+// Generate a fresh agent key and sign the `approveAgent` action for it with the master key.
+// Returns the agent key (hex and wallet handle), its address, the action JSON ready to submit
+// and the master signature over it.
+#[rustler::nif(schedule = "DirtyIo")]
+fn generate_agent<'a>(env: Env<'a>, wallet: WalletRef, agent_name: Option<String>, nonce: u64, is_mainnet: bool) -> NifResult<Term<'a>> {
+    policy::enforce(policy::Request::user_action("approveAgent", is_mainnet))?;
+    let agent = PrivateKeySigner::random_with(&mut rand::rngs::OsRng);
+    let agent_address = agent.address();
+    let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
+
+    let mut action = serde_json::Map::new();
+    action.insert("type".to_string(), JsonValue::from("approveAgent"));
+    action.insert("hyperliquidChain".to_string(), JsonValue::from(hyperliquid_chain.clone()));
+    action.insert("signatureChainId".to_string(), JsonValue::from(format!("{:#x}", signature_chain_id)));
+    action.insert("agentAddress".to_string(), JsonValue::from(agent_address.to_string()));
+    if let Some(name) = &agent_name {
+        action.insert("agentName".to_string(), JsonValue::from(name.clone()));
+    }
+    action.insert("nonce".to_string(), JsonValue::from(nonce));
+
+    let payload = ApproveAgent { signature_chain_id, hyperliquid_chain, agent_address, agent_name, nonce };
+    let audit = audit::Context { action: Some("approveAgent".to_string()), nonce: Some(nonce), ..Default::default() };
+    let sig = sign_typed_data(&payload, &*wallet, audit)?;
+
+    let mut map = rustler::types::map::map_new(env);
+    map = map
+        .map_put("agent_address".encode(env), agent_address.to_string().encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    map = map
+        .map_put("agent_private_key".encode(env), Zeroizing::new(format!("{:#x}", agent.to_bytes())).as_str().encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    map = map
+        .map_put("action_json".encode(env), JsonValue::Object(action).to_string().encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    map = map
+        .map_put("signature".encode(env), signature_to_map(env, sig, None)?)
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    map = map
+        .map_put("agent_wallet".encode(env), ResourceArc::new(WalletResource::local(agent)).encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    Ok(map)
+}
+
 // Sign an L1 action with the given private key and connection ID
-#[rustler::nif]
-fn sign_l1_action<'a>(env: Env<'a>, private_key_hex: String, connection_id: String, is_mainnet: bool) -> NifResult<Term<'a>> {
-    // Parse the wallet from private key
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(format!("wallet error: {}", e))))?;
-    
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_l1_action<'a>(env: Env<'a>, wallet: WalletRef, connection_id: String, is_mainnet: bool) -> NifResult<Term<'a>> {
     // Parse the connection ID as a B256 hash
     let cid = B256::from_str(&connection_id)
         .map_err(|e| rustler::Error::Term(Box::new(format!("invalid connection_id: {}", e))))?;
-    
+    policy::enforce(policy::Request::opaque("connection id", Some(is_mainnet)))?;
+
     // Sign the L1 action
-    let sig = sign_l1_agent_action(&wallet, cid, is_mainnet)
+    let sig = sign_l1_agent_action(&*wallet, cid, is_mainnet, audit::Context::default())
         .map_err(|e| rustler::Error::Term(Box::new(format!("signing failed: {}", e))))?;
 
     // Convert the signature to a map and return
@@ -750,25 +1310,8 @@ fn to_checksum_address(address: String) -> NifResult<String> {
 }
 
 #[rustler::nif]
-fn derive_address(private_key_hex: String) -> NifResult<String> {
-    let wallet = parse_wallet(&private_key_hex)
-        .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?;
+fn derive_address(wallet: WalletRef) -> NifResult<String> {
     Ok(format!("{}", wallet.address()))
 }
 
-rustler::init!("Elixir.Hyperliquid.Signer", [
-    compute_connection_id,
-    compute_connection_id_ex,
-    derive_address,
-    sign_exchange_action,
-    sign_exchange_action_ex,
-    sign_l1_action,
-    sign_multi_sig_action_ex,
-    sign_typed_data,
-    sign_usd_send,
-    sign_withdraw3,
-    sign_spot_send,
-    sign_approve_builder_fee,
-    sign_approve_agent,
-    to_checksum_address,
-]);
+rustler::init!("Elixir.Hyperliquid.Signer");
@@ -0,0 +1,50 @@
+// ===== BIP-39 mnemonic / BIP-32 HD derivation =====
+//
+// Derives secp256k1 keys from an English BIP-39 phrase (plus optional passphrase) along a
+// BIP-32 path, defaulting to the standard Ethereum path m/44'/60'/0'/0/0. The seed stretch is
+// 2048 rounds of PBKDF2, so these NIFs run on the dirty CPU scheduler.
+
+use alloy::signers::local::{coins_bip39::English, MnemonicBuilder, PrivateKeySigner};
+use rustler::{NifResult, ResourceArc};
+
+use crate::{Error, SecretString, WalletResource};
+
+const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+// Phrase errors are reported without detail: the underlying error names the offending word.
+pub(crate) fn derive_wallet(
+    phrase: &SecretString,
+    passphrase: Option<&SecretString>,
+    derivation_path: Option<String>,
+) -> Result<PrivateKeySigner, Error> {
+    let mut builder = MnemonicBuilder::<English>::default()
+        .phrase(phrase.expose())
+        .derivation_path(derivation_path.as_deref().unwrap_or(DEFAULT_DERIVATION_PATH))
+        .map_err(|e| Error::Mnemonic(e.to_string()))?;
+    if let Some(passphrase) = passphrase {
+        builder = builder.password(passphrase.expose());
+    }
+    builder.build().map_err(|_| Error::Mnemonic("invalid mnemonic phrase".to_string()))
+}
+
+// Derive a wallet handle from a mnemonic. `derivation_path` defaults to m/44'/60'/0'/0/0.
+#[rustler::nif(schedule = "DirtyCpu")]
+fn load_mnemonic(
+    phrase: SecretString,
+    passphrase: Option<SecretString>,
+    derivation_path: Option<String>,
+) -> NifResult<ResourceArc<WalletResource>> {
+    let signer = derive_wallet(&phrase, passphrase.as_ref(), derivation_path)?;
+    Ok(ResourceArc::new(WalletResource::local(signer)))
+}
+
+// Derive only the checksummed address for a mnemonic + path (same format as `derive_address`).
+#[rustler::nif(schedule = "DirtyCpu")]
+fn derive_mnemonic_address(
+    phrase: SecretString,
+    passphrase: Option<SecretString>,
+    derivation_path: Option<String>,
+) -> NifResult<String> {
+    let signer = derive_wallet(&phrase, passphrase.as_ref(), derivation_path)?;
+    Ok(format!("{}", signer.address()))
+}
@@ -0,0 +1,240 @@
+// ===== Multi-sig actions =====
+//
+// An action of a multi-sig account (see `convertToMultiSigUser`) is signed in two layers:
+//
+//   1. every co-signer signs the inner action wrapped as `[multiSigUser, outerSigner, action]`,
+//      both addresses lowercase. For an L1 action that is an ordinary L1 signature over the
+//      connection id of the envelope, with the nonce, vault and expiresAfter of the request. For a
+//      user-signed action it is the action's `HyperliquidTransaction:*` struct with
+//      `payloadMultiSigUser` and `outerSigner` members added after `hyperliquidChain`.
+//   2. the outer signer, the co-signer who submits, collects those signatures into
+//
+//        {"type": "multiSig", "signatureChainId": ..., "signatures": [{"r", "s", "v"}, ...],
+//         "payload": {"multiSigUser": ..., "outerSigner": ..., "action": inner}}
+//
+//      and signs `SendMultiSig` over the hash of that action without `type`
+//      (`sign_multi_sig_action_ex/6`).
+//
+//   sig  = Signer.sign_multi_sig_l1_payload(cosigner, action, multi_sig_user, outer, nonce, true, nil, nil)
+//   json = Signer.assemble_multi_sig_action(multi_sig_user, outer, action, [sig, ...], true)
+//   Signer.sign_multi_sig_action_ex(outer_wallet, json, nonce, true, nil, nil)
+//
+// or, for a user-signed action, whose nonce is its own `time` / `nonce`:
+//
+//   sig = Signer.sign_multi_sig_user_signed_payload(cosigner, usd_send, multi_sig_user, outer, true)
+//   Signer.finalize_multi_sig_action(outer_wallet, multi_sig_user, usd_send, [sig, ...], time, true, nil, nil)
+
+use std::str::FromStr;
+
+use alloy::primitives::{Address, B256, U256};
+use rustler::{Encoder, Env, NifResult, Term};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::encode::{decode_action, put, term_to_json};
+use crate::preimage::Preimage;
+use crate::user_signed::{self, action_nonce, complete_action, UserSigned};
+use crate::{
+    action_type, audit, chain, l1_action, parse_address, parse_optional_address, policy, sign_l1_agent_action, sign_multi_sig_value, sign_typed_data,
+    signature_to_map, ticks, Actions, Error, SendMultiSig, WalletRef,
+};
+
+// A co-signer signature as it appears in `signatures`. `r` and `s` are hashed as minimal hex
+// ("0x1f..", no leading zeros) like the reference SDK sends them, so they are normalized here and
+// the zero-padded signature maps returned by the signing functions can be passed in as they are.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MsSignature {
+    #[serde(deserialize_with = "word")]
+    pub r: String,
+    #[serde(deserialize_with = "word")]
+    pub s: String,
+    pub v: u8,
+}
+
+fn word<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    let value = U256::from_str(&hex).map_err(|_| serde::de::Error::custom(format!("invalid signature component {hex:?}")))?;
+    Ok(format!("{value:#x}"))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MsPayload { pub multi_sig_user: String, pub outer_signer: String, pub action: Actions }
+
+// The `multiSig` action without its `type`, which is what `SendMultiSig` hashes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MsAction { pub signature_chain_id: String, pub signatures: Vec<MsSignature>, pub payload: MsPayload }
+
+impl MsAction {
+    pub fn new(multi_sig_user: Address, outer_signer: Address, action: Actions, signatures: Vec<MsSignature>, is_mainnet: bool) -> MsAction {
+        let (signature_chain_id, _) = chain(is_mainnet);
+        MsAction {
+            signature_chain_id: format!("{signature_chain_id:#x}"),
+            signatures,
+            payload: MsPayload { multi_sig_user: format!("{multi_sig_user:#x}"), outer_signer: format!("{outer_signer:#x}"), action },
+        }
+    }
+
+    // Wire body: `type` first, then the hashed fields.
+    pub fn to_json(&self) -> Result<JsonValue, Error> {
+        let mut body = serde_json::Map::new();
+        body.insert("type".to_string(), JsonValue::from("multiSig"));
+        match serde_json::to_value(self).map_err(|e| Error::JsonParse(e.to_string()))? {
+            JsonValue::Object(fields) => body.extend(fields),
+            _ => return Err(Error::GenericParse("multiSig action must serialize to a map".to_string())),
+        }
+        Ok(JsonValue::Object(body))
+    }
+
+    // `SendMultiSig` payload the outer signer signs for this action.
+    pub fn send_multi_sig(&self, nonce: u64, is_mainnet: bool, vault_address: Option<Address>, expires_after: Option<u64>) -> Result<SendMultiSig, Error> {
+        let signature_chain_id = self
+            .signature_chain_id
+            .strip_prefix("0x")
+            .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| Error::GenericParse(format!("invalid signatureChainId {:?}", self.signature_chain_id)))?;
+        let (_, hyperliquid_chain) = chain(is_mainnet);
+        let multi_sig_action_hash = hash_ms_action_with_exp(self, nonce, vault_address, expires_after)?;
+        Ok(SendMultiSig { signature_chain_id, hyperliquid_chain, multi_sig_action_hash, nonce })
+    }
+}
+
+// `multiSigActionHash`: the connection id of the action without its `type`.
+pub fn hash_ms_action_with_exp(action: &MsAction, nonce: u64, vault_address: Option<Address>, expires_after: Option<u64>) -> Result<B256, Error> {
+    Ok(Preimage::new(action, nonce, vault_address, expires_after)?.connection_id())
+}
+
+// Connection id a co-signer signs for an L1 inner action.
+pub fn l1_envelope_connection_id(
+    multi_sig_user: Address,
+    outer_signer: Address,
+    action: &Actions,
+    nonce: u64,
+    vault_address: Option<Address>,
+    expires_after: Option<u64>,
+) -> Result<B256, Error> {
+    let envelope = (format!("{multi_sig_user:#x}"), format!("{outer_signer:#x}"), action);
+    Ok(Preimage::new(&envelope, nonce, vault_address, expires_after)?.connection_id())
+}
+
+// Co-signer signature over an L1 action of a multi-sig account. The inner action goes through the
+// signing policy and tick checks like any L1 action.
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_multi_sig_l1_payload<'a>(
+    env: Env<'a>,
+    wallet: WalletRef,
+    action: Term<'a>,
+    multi_sig_user: String,
+    outer_signer: String,
+    nonce: u64,
+    is_mainnet: bool,
+    vault_address: Option<String>,
+    expires_after: Option<u64>,
+) -> NifResult<Term<'a>> {
+    let action = decode_action(action)?;
+    let action = l1_action(action)?;
+    let multi_sig_user = parse_address("multi_sig_user", &multi_sig_user)?;
+    let outer_signer = parse_address("outer_signer", &outer_signer)?;
+    let vault = parse_optional_address(vault_address)?;
+
+    policy::enforce(policy::Request::action(&action, is_mainnet))?;
+    ticks::enforce(&action)?;
+
+    let cid = l1_envelope_connection_id(multi_sig_user, outer_signer, &action, nonce, vault, expires_after)?;
+
+    let audit = audit::Context { action: Some(action_type(&action)), nonce: Some(nonce), vault_address: vault, ..Default::default() };
+    let sig = sign_l1_agent_action(&*wallet, cid, is_mainnet, audit)?;
+
+    signature_to_map(env, sig, Some(cid))
+}
+
+// Co-signer signature over a user-signed action of a multi-sig account. `hyperliquidChain` and
+// `signatureChainId` are filled in like `sign_user_signed_action/3` does; the result carries
+// `action_json`, the inner action as it goes into the `multiSig` payload.
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_multi_sig_user_signed_payload<'a>(
+    env: Env<'a>,
+    wallet: WalletRef,
+    action: Term<'a>,
+    multi_sig_user: String,
+    outer_signer: String,
+    is_mainnet: bool,
+) -> NifResult<Term<'a>> {
+    let value = term_to_json(action)?;
+    let action = complete_action(value, is_mainnet)?;
+    let multi_sig_user = parse_address("multi_sig_user", &multi_sig_user)?;
+    let outer_signer = parse_address("outer_signer", &outer_signer)?;
+    let action_type = action_type(&action);
+    let canonical = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+
+    policy::enforce(policy::Request::action_json(&canonical, is_mainnet))?;
+
+    let payload = UserSigned::multi_sig(&canonical, multi_sig_user, outer_signer)?;
+    let audit = audit::Context { action: Some(action_type), nonce: action_nonce(&canonical), ..Default::default() };
+    let sig = sign_typed_data(&payload, &*wallet, audit)?;
+
+    let map = signature_to_map(env, sig, None)?;
+    put(map, "action_json", canonical.to_string().encode(env))
+}
+
+// The inner action as it is wrapped. A user-signed action gets its network fields filled in the
+// same way its co-signers signed it.
+pub(crate) fn inner_action(value: JsonValue, is_mainnet: bool) -> Result<Actions, Error> {
+    match value.get("type").and_then(JsonValue::as_str) {
+        Some(action_type) if user_signed::lookup(action_type).is_some() => complete_action(value, is_mainnet),
+        _ => serde_json::from_value(value).map_err(|e| Error::JsonParse(e.to_string())),
+    }
+}
+
+fn assemble(multi_sig_user: &str, outer_signer: Address, action: Term, signatures: Term, is_mainnet: bool) -> Result<JsonValue, Error> {
+    let multi_sig_user = parse_address("multi_sig_user", multi_sig_user)?;
+    let action = inner_action(term_to_json(action)?, is_mainnet)?;
+    let signatures: Vec<MsSignature> = serde_json::from_value(term_to_json(signatures)?)
+        .map_err(|e| Error::GenericParse(format!("invalid signatures: {e}")))?;
+    if signatures.is_empty() {
+        return Err(Error::GenericParse("a multiSig action needs at least one signature".to_string()));
+    }
+    MsAction::new(multi_sig_user, outer_signer, action, signatures, is_mainnet).to_json()
+}
+
+// Wrap an inner action and its co-signer signatures into the `multiSig` action JSON that the outer
+// signer signs with `sign_multi_sig_action_ex/6` and submits.
+#[rustler::nif]
+fn assemble_multi_sig_action<'a>(
+    multi_sig_user: String,
+    outer_signer: String,
+    action: Term<'a>,
+    signatures: Term<'a>,
+    is_mainnet: bool,
+) -> NifResult<String> {
+    parse_address("outer_signer", &outer_signer)
+        .and_then(|outer_signer| assemble(&multi_sig_user, outer_signer, action, signatures, is_mainnet))
+        .map(|value| value.to_string())
+        .map_err(rustler::Error::from)
+}
+
+// `assemble_multi_sig_action/5` followed by `sign_multi_sig_action_ex/6`, with the wallet as the
+// outer signer. Returns the outer signature and `action_json`, the body to submit.
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif(schedule = "DirtyIo")]
+fn finalize_multi_sig_action<'a>(
+    env: Env<'a>,
+    wallet: WalletRef,
+    multi_sig_user: String,
+    action: Term<'a>,
+    signatures: Term<'a>,
+    nonce: u64,
+    is_mainnet: bool,
+    vault_address: Option<String>,
+    expires_after: Option<u64>,
+) -> NifResult<Term<'a>> {
+    let value = assemble(&multi_sig_user, wallet.address(), action, signatures, is_mainnet)?;
+    let vault = parse_optional_address(vault_address)?;
+
+    let sig = sign_multi_sig_value(&*wallet, &value, nonce, is_mainnet, vault, expires_after)?;
+
+    let map = signature_to_map(env, sig, None)?;
+    put(map, "action_json", value.to_string().encode(env))
+}
@@ -0,0 +1,401 @@
+// ===== Pending multi-sig bundles =====
+//
+// Co-signers of a multi-sig account often sign on different machines, some of them offline. A
+// bundle carries everything a co-signer needs to check and sign a multi-sig action and collects
+// the signatures until the threshold is met:
+//
+//   {"version":1,"isMainnet":true,"multiSigUser":"0x..","outerSigner":"0x..","action":{..},
+//    "nonce":..,"vaultAddress":null,"expiresAfter":null,"threshold":2,
+//    "authorizedSigners":["0x..",..],"signatures":[{"signer":"0x..","r":"0x..","s":"0x..","v":27}]}
+//
+// The compact form is the same bundle as msgpack, base64url encoded without padding, for pasting
+// into a chat or a QR code. Every function that takes a bundle accepts either form and returns the
+// form it was given.
+//
+// A signature is only added once it recovers to an authorized signer that has not signed yet.
+// `verify` recovers every signature again, so a bundle edited in transit is caught before
+// `finalize` wraps the signatures into the `multiSig` action and signs it as the outer signer.
+// The `multi_sig_bundle` binary offers the same operations on machines without Elixir.
+
+use alloy::primitives::{Address, Signature as AlloySignature, B256};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use rustler::{Encoder, Env, NifResult, Term};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::backend::SignerBackend;
+use crate::encode::{put, term_to_json};
+use crate::multi_sig::{inner_action, l1_envelope_connection_id, MsAction, MsSignature};
+use crate::recover::{parse_signature, recover_digest};
+use crate::user_signed::{action_nonce, UserSigned};
+use crate::{
+    action_type, audit, l1_agent_payload, parse_address, parse_optional_address, policy, sign_l1_agent_action, sign_typed_data,
+    signature_to_map, ticks, Actions, Eip712, Error, WalletRef,
+};
+
+const VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BundleSignature {
+    pub signer: Address,
+    pub r: String,
+    pub s: String,
+    pub v: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Bundle {
+    pub version: u8,
+    pub is_mainnet: bool,
+    pub multi_sig_user: Address,
+    pub outer_signer: Address,
+    // Inner action in canonical wire form.
+    pub action: JsonValue,
+    pub nonce: u64,
+    pub vault_address: Option<Address>,
+    pub expires_after: Option<u64>,
+    pub threshold: usize,
+    pub authorized_signers: Vec<Address>,
+    pub signatures: Vec<BundleSignature>,
+}
+
+// Outcome of recovering one contained signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureCheck {
+    pub signer: Address,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    pub checks: Vec<SignatureCheck>,
+    pub valid: usize,
+    // Every signature is valid and there are at least `threshold` of them.
+    pub ready: bool,
+}
+
+// The signed `multiSig` action, ready to submit with the bundle's nonce, vault and expiresAfter.
+#[derive(Debug, Clone)]
+pub struct Finalized {
+    pub action: JsonValue,
+    pub digest: B256,
+    pub signature: AlloySignature,
+}
+
+// What a co-signer signs: the L1 `Agent` struct over the envelope's connection id, or the
+// multi-sig variant of a user-signed struct.
+enum Inner {
+    L1(B256),
+    UserSigned(UserSigned),
+}
+
+fn err(msg: impl Into<String>) -> Error {
+    Error::GenericParse(msg.into())
+}
+
+impl Bundle {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        is_mainnet: bool,
+        multi_sig_user: Address,
+        outer_signer: Address,
+        action: JsonValue,
+        nonce: u64,
+        vault_address: Option<Address>,
+        expires_after: Option<u64>,
+        threshold: usize,
+        authorized_signers: Vec<Address>,
+    ) -> Result<Bundle, Error> {
+        let action = inner_action(action, is_mainnet)?;
+        let canonical = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+        if action.is_user_signed() && action_nonce(&canonical) != Some(nonce) {
+            return Err(err(format!("the nonce of a user-signed action must be its own time or nonce, not {nonce}")));
+        }
+        for (i, signer) in authorized_signers.iter().enumerate() {
+            if authorized_signers[..i].contains(signer) {
+                return Err(err(format!("authorized signer {signer} is listed twice")));
+            }
+        }
+        if threshold == 0 || threshold > authorized_signers.len() {
+            return Err(err(format!("threshold must be between 1 and {}", authorized_signers.len())));
+        }
+        if !authorized_signers.contains(&outer_signer) {
+            return Err(err(format!("outer signer {outer_signer} is not an authorized signer")));
+        }
+        Ok(Bundle {
+            version: VERSION,
+            is_mainnet,
+            multi_sig_user,
+            outer_signer,
+            action: canonical,
+            nonce,
+            vault_address,
+            expires_after,
+            threshold,
+            authorized_signers,
+            signatures: Vec::new(),
+        })
+    }
+
+    // JSON or compact form.
+    pub fn parse(text: &str) -> Result<Bundle, Error> {
+        let text = text.trim();
+        let bundle: Bundle = if is_json(text) {
+            serde_json::from_str(text).map_err(|e| Error::JsonParse(e.to_string()))?
+        } else {
+            let bytes = URL_SAFE_NO_PAD.decode(text).map_err(|e| err(format!("invalid compact bundle: {e}")))?;
+            rmp_serde::from_slice(&bytes).map_err(|e| Error::RmpParse(e.to_string()))?
+        };
+        if bundle.version != VERSION {
+            return Err(err(format!("unsupported bundle version {}", bundle.version)));
+        }
+        Ok(bundle)
+    }
+
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string(self).map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    pub fn to_compact(&self) -> Result<String, Error> {
+        let bytes = rmp_serde::to_vec_named(self).map_err(|e| Error::RmpParse(e.to_string()))?;
+        Ok(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    // Re-encode in the form `like` was given in.
+    pub fn encode_like(&self, like: &str) -> Result<String, Error> {
+        if is_json(like.trim()) { self.to_json() } else { self.to_compact() }
+    }
+
+    fn inner_action(&self) -> Result<Actions, Error> {
+        serde_json::from_value(self.action.clone()).map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    fn inner(&self) -> Result<Inner, Error> {
+        let action = self.inner_action()?;
+        if action.is_user_signed() {
+            return Ok(Inner::UserSigned(UserSigned::multi_sig(&self.action, self.multi_sig_user, self.outer_signer)?));
+        }
+        let cid = l1_envelope_connection_id(self.multi_sig_user, self.outer_signer, &action, self.nonce, self.vault_address, self.expires_after)?;
+        Ok(Inner::L1(cid))
+    }
+
+    // EIP-712 digest every co-signer signs.
+    pub fn digest(&self) -> Result<B256, Error> {
+        Ok(match self.inner()? {
+            Inner::L1(cid) => l1_agent_payload(cid, self.is_mainnet).eip712_signing_hash(),
+            Inner::UserSigned(payload) => payload.eip712_signing_hash(),
+        })
+    }
+
+    // Recover `signature`, check it against the authorized signers and add it. Returns the signer.
+    pub fn add_signature(&mut self, signature: &AlloySignature) -> Result<Address, Error> {
+        let signer = recover_digest(signature, &self.digest()?)?;
+        if !self.authorized_signers.contains(&signer) {
+            return Err(err(format!("{signer} is not an authorized signer")));
+        }
+        if self.signatures.iter().any(|s| s.signer == signer) {
+            return Err(err(format!("{signer} has already signed")));
+        }
+        self.signatures.push(BundleSignature {
+            signer,
+            r: format!("{:#x}", signature.r()),
+            s: format!("{:#x}", signature.s()),
+            v: 27 + u8::from(signature.v()),
+        });
+        Ok(signer)
+    }
+
+    // Sign as a co-signer and add the signature.
+    pub fn sign(&mut self, wallet: &dyn SignerBackend) -> Result<Address, Error> {
+        let audit = audit::Context {
+            action: Some(action_type(&self.inner_action()?)),
+            nonce: Some(self.nonce),
+            vault_address: self.vault_address,
+            ..Default::default()
+        };
+        let signature = match self.inner()? {
+            Inner::L1(cid) => sign_l1_agent_action(wallet, cid, self.is_mainnet, audit)?,
+            Inner::UserSigned(payload) => sign_typed_data(&payload, wallet, audit)?,
+        };
+        self.add_signature(&signature)
+    }
+
+    pub fn verify(&self) -> Verification {
+        let digest = self.digest();
+        let checks: Vec<SignatureCheck> = self
+            .signatures
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let error = match &digest {
+                    Err(e) => Some(e.to_string()),
+                    Ok(digest) => self.check(entry, digest, &self.signatures[..i]).err().map(|e| e.to_string()),
+                };
+                SignatureCheck { signer: entry.signer, error }
+            })
+            .collect();
+        let valid = checks.iter().filter(|c| c.error.is_none()).count();
+        Verification { ready: valid == checks.len() && valid >= self.threshold, valid, checks }
+    }
+
+    fn check(&self, entry: &BundleSignature, digest: &B256, earlier: &[BundleSignature]) -> Result<(), Error> {
+        let signature = parse_signature(&entry.r, &entry.s, u64::from(entry.v))?;
+        let recovered = recover_digest(&signature, digest)?;
+        if recovered != entry.signer {
+            return Err(err(format!("signature recovers to {recovered}")));
+        }
+        if !self.authorized_signers.contains(&entry.signer) {
+            return Err(err(format!("{} is not an authorized signer", entry.signer)));
+        }
+        if earlier.iter().any(|s| s.signer == entry.signer) {
+            return Err(err(format!("{} signed more than once", entry.signer)));
+        }
+        Ok(())
+    }
+
+    // The `multiSig` action, once every signature verifies and the threshold is met.
+    pub fn multi_sig_action(&self) -> Result<MsAction, Error> {
+        let verification = self.verify();
+        if let Some(bad) = verification.checks.iter().find_map(|c| c.error.as_ref().map(|e| (c.signer, e))) {
+            return Err(err(format!("invalid signature from {}: {}", bad.0, bad.1)));
+        }
+        if !verification.ready {
+            return Err(err(format!("{} of {} required signatures", verification.valid, self.threshold)));
+        }
+        let signatures = self.signatures.iter().map(|s| MsSignature { r: s.r.clone(), s: s.s.clone(), v: s.v }).collect();
+        Ok(MsAction::new(self.multi_sig_user, self.outer_signer, self.inner_action()?, signatures, self.is_mainnet))
+    }
+
+    // Sign the `multiSig` action as the outer signer.
+    pub fn finalize(&self, wallet: &dyn SignerBackend) -> Result<Finalized, Error> {
+        if wallet.address() != self.outer_signer {
+            return Err(err(format!("the bundle must be finalized by its outer signer {}", self.outer_signer)));
+        }
+        let action = self.multi_sig_action()?;
+        let payload = action.send_multi_sig(self.nonce, self.is_mainnet, self.vault_address, self.expires_after)?;
+        let audit = audit::Context {
+            action: Some("multiSig".to_string()),
+            nonce: Some(self.nonce),
+            vault_address: self.vault_address,
+            ..Default::default()
+        };
+        let signature = sign_typed_data(&payload, wallet, audit)?;
+        Ok(Finalized { action: action.to_json()?, digest: payload.eip712_signing_hash(), signature })
+    }
+}
+
+fn is_json(text: &str) -> bool {
+    text.starts_with('{')
+}
+
+// Create an empty bundle for an inner action. Returns its JSON form.
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn new_multi_sig_bundle<'a>(
+    multi_sig_user: String,
+    outer_signer: String,
+    action: Term<'a>,
+    nonce: u64,
+    threshold: usize,
+    authorized_signers: Vec<String>,
+    is_mainnet: bool,
+    vault_address: Option<String>,
+    expires_after: Option<u64>,
+) -> NifResult<String> {
+    let multi_sig_user = parse_address("multi_sig_user", &multi_sig_user)?;
+    let outer_signer = parse_address("outer_signer", &outer_signer)?;
+    let authorized_signers =
+        authorized_signers.iter().map(|a| parse_address("authorized signer", a)).collect::<Result<Vec<_>, _>>()?;
+    let vault = parse_optional_address(vault_address)?;
+    let action = term_to_json(action)?;
+    let bundle = Bundle::new(is_mainnet, multi_sig_user, outer_signer, action, nonce, vault, expires_after, threshold, authorized_signers)?;
+    Ok(bundle.to_json()?)
+}
+
+#[rustler::nif]
+fn multi_sig_bundle_to_compact(bundle: String) -> NifResult<String> {
+    Ok(Bundle::parse(&bundle)?.to_compact()?)
+}
+
+#[rustler::nif]
+fn multi_sig_bundle_to_json(bundle: String) -> NifResult<String> {
+    Ok(Bundle::parse(&bundle)?.to_json()?)
+}
+
+// Sign a bundle as a co-signer. The inner action goes through the signing policy (and, for an L1
+// action, the tick checks) like any other signature.
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_multi_sig_bundle(wallet: WalletRef, bundle: String) -> NifResult<String> {
+    let mut parsed = Bundle::parse(&bundle)?;
+    let action = parsed.inner_action()?;
+    if action.is_user_signed() {
+        policy::enforce(policy::Request::action_json(&parsed.action, parsed.is_mainnet))?;
+    } else {
+        policy::enforce(policy::Request::action(&action, parsed.is_mainnet))?;
+        ticks::enforce(&action)?;
+    }
+    parsed.sign(&*wallet)?;
+    Ok(parsed.encode_like(&bundle)?)
+}
+
+#[derive(Deserialize)]
+struct SignatureArg {
+    r: String,
+    s: String,
+    v: u64,
+}
+
+// Add a signature produced elsewhere (`%{r, s, v}`); it must recover to an authorized signer.
+#[rustler::nif]
+fn add_multi_sig_bundle_signature<'a>(bundle: String, signature: Term<'a>) -> NifResult<String> {
+    let mut parsed = Bundle::parse(&bundle)?;
+    let arg: SignatureArg = serde_json::from_value(term_to_json(signature)?)
+        .map_err(|e| err(format!("invalid signature: {e}")))?;
+    let signature = parse_signature(&arg.r, &arg.s, arg.v)?;
+    parsed.add_signature(&signature)?;
+    Ok(parsed.encode_like(&bundle)?)
+}
+
+// Recover every contained signature:
+// %{"signatures" => [%{"signer" => .., "valid" => .., "error" => ..}], "valid" => n, "threshold" => k, "ready" => bool}
+#[rustler::nif]
+fn verify_multi_sig_bundle<'a>(env: Env<'a>, bundle: String) -> NifResult<Term<'a>> {
+    let parsed = Bundle::parse(&bundle)?;
+    let verification = parsed.verify();
+    let checks = verification
+        .checks
+        .iter()
+        .map(|check| {
+            let mut map = rustler::types::map::map_new(env);
+            map = put(map, "signer", check.signer.to_string().encode(env))?;
+            map = put(map, "valid", check.error.is_none().encode(env))?;
+            put(map, "error", check.error.encode(env))
+        })
+        .collect::<NifResult<Vec<Term<'a>>>>()?;
+    let mut map = rustler::types::map::map_new(env);
+    map = put(map, "signatures", checks.encode(env))?;
+    map = put(map, "valid", verification.valid.encode(env))?;
+    map = put(map, "threshold", parsed.threshold.encode(env))?;
+    map = put(map, "ready", verification.ready.encode(env))?;
+    Ok(map)
+}
+
+// Wrap the collected signatures into the `multiSig` action and sign it; the wallet must be the
+// bundle's outer signer. Returns the signature, `action_json` and the bundle's `nonce`,
+// `vault_address` and `expires_after` to submit with it.
+#[rustler::nif(schedule = "DirtyIo")]
+fn finalize_multi_sig_bundle<'a>(env: Env<'a>, wallet: WalletRef, bundle: String) -> NifResult<Term<'a>> {
+    let parsed = Bundle::parse(&bundle)?;
+    let action = parsed.multi_sig_action().and_then(|a| a.to_json())?;
+    policy::enforce(policy::Request::multi_sig(&action, parsed.is_mainnet))?;
+    let finalized = parsed.finalize(&*wallet)?;
+
+    let mut map = signature_to_map(env, finalized.signature, None)?;
+    map = put(map, "action_json", finalized.action.to_string().encode(env))?;
+    map = put(map, "nonce", parsed.nonce.encode(env))?;
+    map = put(map, "vault_address", parsed.vault_address.map(|a| a.to_string()).encode(env))?;
+    put(map, "expires_after", parsed.expires_after.encode(env))
+}
+
@@ -0,0 +1,158 @@
+// ===== Network profiles =====
+//
+// Every network constant that ends up in a signature lives here:
+//
+//   profile   hyperliquidChain   signatureChainId   Agent domain chain id   Agent source
+//   mainnet   "Mainnet"          0xa4b1             1337                    "a"
+//   testnet   "Testnet"          0x66eee            1337                    "b"
+//
+// Elixir installs the active profile once at load with `set_network_profile/1`, from
+// `Hyperliquid.Config`. It is either `:mainnet`, `:testnet` or a map describing a local devnet;
+// fields a map leaves out are taken from the built-in profile on the same side:
+//
+//   %{name: "devnet", is_mainnet: false, signature_chain_id: "0x539", agent_source: "b"}
+//
+// The signing NIFs keep their `is_mainnet` argument: the installed profile is used when it is for
+// that side, the built-in one otherwise.
+
+use std::sync::RwLock;
+
+use rustler::{Atom, Encoder, Env, NifResult, Term};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value as JsonValue;
+
+use crate::encode::{put, term_to_json};
+use crate::Error;
+
+rustler::atoms! { ok }
+
+const AGENT_CHAIN_ID: u64 = 1337;
+
+static PROFILE: RwLock<Option<NetworkProfile>> = RwLock::new(None);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub is_mainnet: bool,
+    pub hyperliquid_chain: String,
+    pub signature_chain_id: u64,
+    pub agent_chain_id: u64,
+    pub agent_source: String,
+}
+
+impl NetworkProfile {
+    pub fn mainnet() -> NetworkProfile {
+        NetworkProfile {
+            name: "mainnet".to_string(),
+            is_mainnet: true,
+            hyperliquid_chain: "Mainnet".to_string(),
+            signature_chain_id: 0xa4b1,
+            agent_chain_id: AGENT_CHAIN_ID,
+            agent_source: "a".to_string(),
+        }
+    }
+
+    pub fn testnet() -> NetworkProfile {
+        NetworkProfile {
+            name: "testnet".to_string(),
+            is_mainnet: false,
+            hyperliquid_chain: "Testnet".to_string(),
+            signature_chain_id: 0x66eee,
+            agent_chain_id: AGENT_CHAIN_ID,
+            agent_source: "b".to_string(),
+        }
+    }
+
+    fn builtin(is_mainnet: bool) -> NetworkProfile {
+        if is_mainnet { NetworkProfile::mainnet() } else { NetworkProfile::testnet() }
+    }
+
+    pub fn parse(value: JsonValue) -> Result<NetworkProfile, Error> {
+        match value {
+            JsonValue::String(name) => match name.as_str() {
+                "mainnet" => Ok(NetworkProfile::mainnet()),
+                "testnet" => Ok(NetworkProfile::testnet()),
+                other => Err(Error::GenericParse(format!("unknown network {other:?}; expected mainnet, testnet or a profile map"))),
+            },
+            value => {
+                let custom: Custom = serde_json::from_value(value).map_err(|e| Error::GenericParse(format!("invalid network profile: {e}")))?;
+                let base = NetworkProfile::builtin(custom.is_mainnet);
+                Ok(NetworkProfile {
+                    name: custom.name,
+                    is_mainnet: custom.is_mainnet,
+                    hyperliquid_chain: custom.hyperliquid_chain.unwrap_or(base.hyperliquid_chain),
+                    signature_chain_id: custom.signature_chain_id.unwrap_or(base.signature_chain_id),
+                    agent_chain_id: custom.agent_chain_id.unwrap_or(base.agent_chain_id),
+                    agent_source: custom.agent_source.unwrap_or(base.agent_source),
+                })
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Custom {
+    name: String,
+    #[serde(default)] is_mainnet: bool,
+    #[serde(default)] hyperliquid_chain: Option<String>,
+    #[serde(default, deserialize_with = "chain_id")] signature_chain_id: Option<u64>,
+    #[serde(default, deserialize_with = "chain_id")] agent_chain_id: Option<u64>,
+    #[serde(default)] agent_source: Option<String>,
+}
+
+// Chain ids may be written as integers or as hex strings ("0x66eee").
+fn chain_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw { Number(u64), Text(String) }
+    match Raw::deserialize(deserializer)? {
+        Raw::Number(n) => Ok(Some(n)),
+        Raw::Text(s) => {
+            let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).ok_or_else(|| serde::de::Error::custom("chain id strings must be hex"))?;
+            u64::from_str_radix(hex, 16).map(Some).map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+// Profile for one side of the `is_mainnet` switch.
+pub fn profile(is_mainnet: bool) -> NetworkProfile {
+    let installed = PROFILE.read().unwrap_or_else(|e| e.into_inner());
+    match installed.as_ref() {
+        Some(profile) if profile.is_mainnet == is_mainnet => profile.clone(),
+        _ => NetworkProfile::builtin(is_mainnet),
+    }
+}
+
+impl NetworkProfile {
+    fn to_term<'a>(&self, env: Env<'a>) -> NifResult<Term<'a>> {
+        let mut map = rustler::types::map::map_new(env);
+        map = put(map, "name", self.name.encode(env))?;
+        map = put(map, "is_mainnet", self.is_mainnet.encode(env))?;
+        map = put(map, "hyperliquid_chain", self.hyperliquid_chain.encode(env))?;
+        map = put(map, "signature_chain_id", format!("{:#x}", self.signature_chain_id).encode(env))?;
+        map = put(map, "agent_chain_id", self.agent_chain_id.encode(env))?;
+        put(map, "agent_source", self.agent_source.encode(env))
+    }
+}
+
+// Install the process-wide profile: `:mainnet`, `:testnet` or a profile map.
+#[rustler::nif]
+fn set_network_profile(profile: Term) -> NifResult<Atom> {
+    let profile = term_to_json(profile)
+        .and_then(NetworkProfile::parse)?;
+    *PROFILE.write().unwrap_or_else(|e| e.into_inner()) = Some(profile);
+    Ok(ok())
+}
+
+#[rustler::nif]
+fn clear_network_profile() -> Atom {
+    *PROFILE.write().unwrap_or_else(|e| e.into_inner()) = None;
+    ok()
+}
+
+// The profile the signing NIFs use for `is_mainnet`.
+#[rustler::nif]
+fn network_profile(env: Env, is_mainnet: bool) -> NifResult<Term> {
+    profile(is_mainnet).to_term(env)
+}
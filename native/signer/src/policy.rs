@@ -0,0 +1,329 @@
+// ===== Pre-sign policy =====
+//
+// Optional process-wide policy, loaded from TOML or JSON with `set_policy/2`, that every signing
+// NIF evaluates before a signature is produced. It is defense in depth against bugs in calling
+// code: a violating request comes back as `{:error, {:policy_violation, %{...}}}` and nothing is
+// signed. With no policy loaded every request is allowed.
+//
+//   network = "mainnet"                         # or "testnet"
+//   allowed_actions = ["order", "cancel", "usdSend"]
+//   allowed_destinations = ["0x..."]            # usdSend / withdraw3 / spotSend / sendAsset / sendToEvmWithData
+//   max_transfer_amount = "1000"                # per transfer, including transfers within the account
+//   max_order_notional = "50000"                # per order, limit_px * sz; refuses twapOrder
+//   allow_opaque_signing = false                # sign_l1_action / sign_typed_data
+//
+// Requests whose contents cannot be inspected (a precomputed connection id, arbitrary typed data)
+// are refused unless `allow_opaque_signing` is set. Amounts and notionals are compared as exact
+// decimals.
+
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use alloy::primitives::{Address, U256};
+use rustler::{Encoder, Env, NifResult, Term};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value as JsonValue;
+
+use crate::wire::{self, format_parts, Parts};
+use crate::{action_type, Actions, Error, OrderRequest};
+
+// Decimals of the integer amounts some actions carry: `usd` in micro-USD, `wei` of HYPE.
+const USD_DECIMALS: u32 = 6;
+const WEI_DECIMALS: u32 = 8;
+
+rustler::atoms! { ok, policy_violation }
+
+static POLICY: RwLock<Option<Arc<Policy>>> = RwLock::new(None);
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Network { Mainnet, Testnet }
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)] pub network: Option<Network>,
+    #[serde(default)] pub allowed_actions: Option<BTreeSet<String>>,
+    #[serde(default)] pub allowed_destinations: Option<BTreeSet<Address>>,
+    #[serde(default, deserialize_with = "amount")] pub max_transfer_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "amount")] pub max_order_notional: Option<Amount>,
+    #[serde(default)] pub allow_opaque_signing: bool,
+}
+
+// Limits may be written as numbers or as decimal strings (the wire format for amounts).
+fn amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Amount>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw { Number(f64), Text(String) }
+    let text = match Raw::deserialize(deserializer)? {
+        Raw::Number(n) if n.is_finite() => n.to_string(),
+        Raw::Number(_) => String::new(),
+        Raw::Text(s) => s,
+    };
+    Amount::parse(&text).map(Some).ok_or_else(|| serde::de::Error::custom("limit must be a non-negative number"))
+}
+
+// An exact non-negative decimal, `units / 10^scale`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Amount { units: U256, scale: u32 }
+
+impl Amount {
+    pub fn parse(s: &str) -> Option<Amount> {
+        let Parts { negative, int_digits, frac_digits } = wire::parse_decimal(s).ok()?;
+        let frac_digits = frac_digits.trim_end_matches('0');
+        let digits = format!("{int_digits}{frac_digits}");
+        let units = match digits.trim_start_matches('0') {
+            "" => U256::ZERO,
+            digits => U256::from_str_radix(digits, 10).ok()?,
+        };
+        (!negative || units.is_zero()).then_some(Amount { units, scale: frac_digits.len() as u32 })
+    }
+
+    fn from_units(units: u64, scale: u32) -> Amount {
+        Amount { units: U256::from(units), scale }
+    }
+
+    fn checked_mul(&self, other: &Amount) -> Option<Amount> {
+        Some(Amount { units: self.units.checked_mul(other.units)?, scale: self.scale + other.scale })
+    }
+
+    // Whether `self` is larger than `limit`. Only one side is scaled up, and if that overflows it
+    // is the larger one.
+    fn exceeds(&self, limit: &Amount) -> bool {
+        let scale = |units: U256, by: u32| U256::from(10u8).checked_pow(U256::from(by)).and_then(|p| units.checked_mul(p));
+        if self.scale <= limit.scale {
+            scale(self.units, limit.scale - self.scale).is_none_or(|units| units > limit.units)
+        } else {
+            scale(limit.units, self.scale - limit.scale).is_some_and(|units| self.units > units)
+        }
+    }
+}
+
+impl std::fmt::Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scale = self.scale as usize;
+        let digits = format!("{:0>width$}", self.units.to_string(), width = scale + 1);
+        let (int_digits, frac_digits) = digits.split_at(digits.len() - scale);
+        f.write_str(&format_parts(false, int_digits, frac_digits))
+    }
+}
+
+impl Policy {
+    pub fn parse(source: &str, format: &str) -> Result<Policy, Error> {
+        match format {
+            "toml" => toml::from_str(source).map_err(|e| Error::Policy(e.to_string())),
+            "json" => serde_json::from_str(source).map_err(|e| Error::Policy(e.to_string())),
+            other => Err(Error::Policy(format!("unsupported policy format {other:?}; expected \"toml\" or \"json\""))),
+        }
+    }
+
+    pub fn evaluate(&self, request: &Request) -> Result<(), Violation> {
+        let violation = |rule, message: String| Err(Violation { rule, action: request.action.clone(), message });
+
+        if let (Some(network), Some(is_mainnet)) = (self.network, request.is_mainnet) {
+            if (network == Network::Mainnet) != is_mainnet {
+                let target = if is_mainnet { "mainnet" } else { "testnet" };
+                return violation("network", format!("signing for {target} is not allowed"));
+            }
+        }
+
+        if request.opaque {
+            if !self.allow_opaque_signing {
+                return violation("allow_opaque_signing", format!("{} cannot be inspected by the policy", request.action));
+            }
+            return Ok(());
+        }
+
+        if let Some(allowed) = &self.allowed_actions {
+            if !allowed.contains(&request.action) {
+                return violation("allowed_actions", format!("action {} is not allowed", request.action));
+            }
+        }
+
+        if let Some(transfer) = &request.transfer {
+            if let (Some(allowed), Some(destination)) = (&self.allowed_destinations, &transfer.destination) {
+                match Address::from_str(destination) {
+                    Ok(address) if allowed.contains(&address) => {}
+                    _ => return violation("allowed_destinations", format!("destination {destination} is not allowed")),
+                }
+            }
+            if let Some(max) = &self.max_transfer_amount {
+                match Amount::parse(&transfer.amount) {
+                    Some(amount) if !amount.exceeds(max) => {}
+                    Some(_) => return violation("max_transfer_amount", format!("amount {} exceeds {max}", transfer.amount)),
+                    None => return violation("max_transfer_amount", format!("amount {:?} is not a decimal number", transfer.amount)),
+                }
+            }
+        }
+
+        if let Some(max) = &self.max_order_notional {
+            if request.twap {
+                return violation("max_order_notional", "a TWAP order has no limit price to bound its notional".to_string());
+            }
+            for order in &request.orders {
+                let Some(order) = order else {
+                    return violation("max_order_notional", "order could not be read".to_string());
+                };
+                match (Amount::parse(&order.limit_px), Amount::parse(&order.sz)) {
+                    (Some(px), Some(sz)) => match px.checked_mul(&sz) {
+                        Some(notional) if !notional.exceeds(max) => {}
+                        Some(notional) => return violation("max_order_notional", format!("order notional {notional} exceeds {max}")),
+                        None => return violation("max_order_notional", format!("order notional {px} * {sz} exceeds {max}")),
+                    },
+                    _ => return violation("max_order_notional", format!("order price {:?} or size {:?} is not a decimal number", order.limit_px, order.sz)),
+                }
+            }
+        }
+
+        for inner in &request.inner {
+            self.evaluate(inner)?;
+        }
+        Ok(())
+    }
+}
+
+// What is about to be signed, reduced to the fields the rules look at.
+#[derive(Debug, Clone, Default)]
+pub struct Request {
+    pub action: String,
+    pub is_mainnet: Option<bool>,
+    pub transfer: Option<Transfer>,
+    // `None` marks an order entry that is present but did not parse.
+    pub orders: Vec<Option<OrderRequest>>,
+    // A TWAP order, whose notional is unknown at signing time.
+    pub twap: bool,
+    // Wrapped actions (the inner action of a multiSig envelope).
+    pub inner: Vec<Request>,
+    pub opaque: bool,
+}
+
+// `destination` is `None` for transfers that stay within the signer's own account.
+#[derive(Debug, Clone)]
+pub struct Transfer { pub destination: Option<String>, pub amount: String }
+
+impl Transfer {
+    fn within(amount: String) -> Transfer {
+        Transfer { destination: None, amount }
+    }
+}
+
+impl Request {
+    pub fn action(action: &Actions, is_mainnet: bool) -> Request {
+        let orders = match action {
+            Actions::Order(bulk) => bulk.orders.iter().cloned().map(Some).collect(),
+            Actions::BatchModify(bulk) => bulk.modifies.iter().map(|m| Some(m.order.clone())).collect(),
+            _ => Vec::new(),
+        };
+        let transfer = match action {
+            Actions::VaultTransfer(t) => Some(Transfer::within(Amount::from_units(t.usd, USD_DECIMALS).to_string())),
+            Actions::SubAccountTransfer(t) => Some(Transfer::within(Amount::from_units(t.usd, USD_DECIMALS).to_string())),
+            Actions::SubAccountSpotTransfer(t) => Some(Transfer::within(t.amount.clone())),
+            Actions::CWithdraw(t) => Some(Transfer::within(Amount::from_units(t.wei, WEI_DECIMALS).to_string())),
+            _ => None,
+        };
+        let twap = matches!(action, Actions::TwapOrder(_));
+        Request { action: action_type(action), is_mainnet: Some(is_mainnet), transfer, orders, twap, ..Request::default() }
+    }
+
+    // Untyped action JSON: a user-signed action, or the inner action of a multiSig envelope.
+    pub fn action_json(value: &JsonValue, is_mainnet: bool) -> Request {
+        let action = value.get("type").and_then(JsonValue::as_str).unwrap_or_default().to_string();
+        let order_values = value
+            .get("orders")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+            .chain(value.get("order"))
+            .chain(
+                value
+                    .get("modifies")
+                    .and_then(JsonValue::as_array)
+                    .into_iter()
+                    .flatten()
+                    .map(|m| m.get("order").unwrap_or(&JsonValue::Null)),
+            );
+        let orders = order_values.map(|o| serde_json::from_value(o.clone()).ok()).collect();
+        let text = |key| value.get(key).and_then(JsonValue::as_str).unwrap_or_default().to_string();
+        // A missing or non-integer amount is passed on as text that fails to parse, so a limit refuses it.
+        let units = |key, scale| value.get(key).and_then(JsonValue::as_u64).map_or_else(String::new, |n| Amount::from_units(n, scale).to_string());
+        let transfer = match action.as_str() {
+            "usdSend" | "withdraw3" | "spotSend" | "sendAsset" => Some(Transfer { destination: Some(text("destination")), amount: text("amount") }),
+            "sendToEvmWithData" => Some(Transfer { destination: Some(text("destinationRecipient")), amount: text("amount") }),
+            "usdClassTransfer" => Some(Transfer::within(class_transfer_amount(&text("amount")).to_string())),
+            "vaultTransfer" | "subAccountTransfer" => Some(Transfer::within(units("usd", USD_DECIMALS))),
+            "subAccountSpotTransfer" => Some(Transfer::within(text("amount"))),
+            "cWithdraw" => Some(Transfer::within(units("wei", WEI_DECIMALS))),
+            _ => None,
+        };
+        let twap = action == "twapOrder";
+        Request { action, is_mainnet: Some(is_mainnet), transfer, orders, twap, ..Request::default() }
+    }
+
+    pub fn multi_sig(value: &JsonValue, is_mainnet: bool) -> Request {
+        let inner = value
+            .pointer("/payload/action")
+            .filter(|a| a.is_object())
+            .map(|a| vec![Request::action_json(a, is_mainnet)])
+            .unwrap_or_default();
+        Request { action: "multiSig".to_string(), is_mainnet: Some(is_mainnet), inner, ..Request::default() }
+    }
+
+    pub fn transfer(action: &str, destination: &str, amount: &str, is_mainnet: bool) -> Request {
+        let transfer = Transfer { destination: Some(destination.to_string()), amount: amount.to_string() };
+        Request { action: action.to_string(), is_mainnet: Some(is_mainnet), transfer: Some(transfer), ..Request::default() }
+    }
+
+    // usdClassTransfer moves USDC between the perp and spot balances of one account.
+    pub fn class_transfer(amount: &str, is_mainnet: bool) -> Request {
+        let transfer = Transfer::within(class_transfer_amount(amount).to_string());
+        Request { action: "usdClassTransfer".to_string(), is_mainnet: Some(is_mainnet), transfer: Some(transfer), ..Request::default() }
+    }
+
+    pub fn user_action(action: &str, is_mainnet: bool) -> Request {
+        Request { action: action.to_string(), is_mainnet: Some(is_mainnet), ..Request::default() }
+    }
+
+    pub fn opaque(what: &str, is_mainnet: Option<bool>) -> Request {
+        Request { action: what.to_string(), is_mainnet, opaque: true, ..Request::default() }
+    }
+}
+
+// The amount of a usdClassTransfer, without any " subaccount:<address>" suffix.
+fn class_transfer_amount(amount: &str) -> &str {
+    amount.split_once(" subaccount:").map_or(amount, |(amount, _)| amount)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation { pub rule: &'static str, pub action: String, pub message: String }
+
+impl Encoder for Violation {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let details: HashMap<&str, &str> =
+            HashMap::from([("rule", self.rule), ("action", self.action.as_str()), ("message", self.message.as_str())]);
+        (policy_violation(), details).encode(env)
+    }
+}
+
+// Evaluate `request` against the loaded policy; called by every signing NIF before it signs.
+pub(crate) fn enforce(request: Request) -> NifResult<()> {
+    let policy = POLICY.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match policy {
+        Some(policy) => policy.evaluate(&request).map_err(|v| rustler::Error::Term(Box::new(v))),
+        None => Ok(()),
+    }
+}
+
+// Replace the process-wide policy. `format` is "toml" or "json".
+#[rustler::nif]
+fn set_policy(source: String, format: String) -> NifResult<rustler::Atom> {
+    let policy = Policy::parse(&source, &format)?;
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(policy));
+    Ok(ok())
+}
+
+#[rustler::nif]
+fn clear_policy() -> rustler::Atom {
+    *POLICY.write().unwrap_or_else(|e| e.into_inner()) = None;
+    ok()
+}
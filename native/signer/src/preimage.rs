@@ -0,0 +1,114 @@
+// ===== Connection id preimage =====
+//
+// An L1 connection id is keccak256 over four concatenated segments:
+//
+//   action         msgpack of the action, maps in key order
+//   nonce          nonce as 8 big-endian bytes
+//   vault          0x00, or 0x01 followed by the 20-byte vault address
+//   expires_after  absent, or 0x00 followed by expiresAfter as 8 big-endian bytes
+//
+// `explain_connection_id/4` returns each segment so a rejected signature can be diffed against
+// the preimage another SDK builds; `decode_action_msgpack/1` turns msgpack bytes (ours or theirs)
+// back into JSON with key order intact.
+//
+//   Signer.explain_connection_id(~s({"type":"noop"}), 1, nil, nil)
+//   #=> %{"segments" => [%{"name" => "action", "hex" => "0x81a474797065a46e6f6f70"}, ...],
+//   #     "action_json" => ~s({"type":"noop"}), "preimage" => "0x...", "connection_id" => "0x..."}
+
+use alloy::primitives::{keccak256, Address, B256};
+use rustler::{Binary, Encoder, Env, NifResult, Term};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::encode::put;
+use crate::{parse_optional_address, Error};
+
+pub(crate) struct Preimage {
+    pub action: Vec<u8>,
+    pub nonce: u64,
+    pub vault_address: Option<Address>,
+    pub expires_after: Option<u64>,
+}
+
+impl Preimage {
+    pub fn new<T: Serialize + ?Sized>(action: &T, nonce: u64, vault_address: Option<Address>, expires_after: Option<u64>) -> Result<Preimage, Error> {
+        let action = rmp_serde::to_vec_named(action).map_err(|e| Error::RmpParse(e.to_string()))?;
+        Ok(Preimage { action, nonce, vault_address, expires_after })
+    }
+
+    // Named segments in hashing order; `expires_after` only when set.
+    pub fn segments(&self) -> Vec<(&'static str, Vec<u8>)> {
+        let mut vault = Vec::with_capacity(21);
+        match self.vault_address {
+            Some(address) => {
+                vault.push(1);
+                vault.extend(address);
+            }
+            None => vault.push(0),
+        }
+        let mut segments = vec![("action", self.action.clone()), ("nonce", self.nonce.to_be_bytes().to_vec()), ("vault", vault)];
+        if let Some(exp) = self.expires_after {
+            let mut expires = vec![0];
+            expires.extend(exp.to_be_bytes());
+            segments.push(("expires_after", expires));
+        }
+        segments
+    }
+
+    pub fn bytes(&self) -> Vec<u8> {
+        self.segments().into_iter().flat_map(|(_, bytes)| bytes).collect()
+    }
+
+    pub fn connection_id(&self) -> B256 {
+        keccak256(self.bytes())
+    }
+}
+
+pub fn decode_msgpack(bytes: &[u8]) -> Result<JsonValue, Error> {
+    rmp_serde::from_slice(bytes).map_err(|e| Error::RmpParse(e.to_string()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    format!("0x{}", alloy::hex::encode(bytes))
+}
+
+// Break the connection id of a JSON action down into its preimage segments. Hashes the JSON as
+// given, like `compute_connection_id_ex/4`; pass `encode_action/1`'s `json` to see the typed
+// canonical form that the signing functions hash.
+#[rustler::nif]
+fn explain_connection_id<'a>(
+    env: Env<'a>,
+    action_json: String,
+    nonce: u64,
+    vault_address: Option<String>,
+    expires_after: Option<u64>,
+) -> NifResult<Term<'a>> {
+    let value: JsonValue = serde_json::from_str(&action_json).map_err(|e| Error::JsonParse(e.to_string()))?;
+    let vault = parse_optional_address(vault_address)?;
+    let preimage = Preimage::new(&value, nonce, vault, expires_after)?;
+    let decoded = decode_msgpack(&preimage.action)?;
+
+    let segments = preimage
+        .segments()
+        .iter()
+        .map(|(name, bytes)| {
+            let map = put(rustler::types::map::map_new(env), "name", name.encode(env))?;
+            put(map, "hex", hex(bytes).encode(env))
+        })
+        .collect::<NifResult<Vec<Term<'a>>>>()?;
+
+    let mut map = rustler::types::map::map_new(env);
+    map = put(map, "segments", segments.encode(env))?;
+    map = put(map, "action_json", decoded.to_string().encode(env))?;
+    map = put(map, "preimage", hex(&preimage.bytes()).encode(env))?;
+    map = put(map, "connection_id", format!("{:#x}", preimage.connection_id()).encode(env))?;
+    Ok(map)
+}
+
+// Decode msgpack bytes into a JSON string, keeping map keys in their encoded order.
+#[rustler::nif]
+fn decode_action_msgpack(bytes: Binary) -> NifResult<String> {
+    decode_msgpack(bytes.as_slice())
+        .map(|value| value.to_string())
+        .map_err(rustler::Error::from)
+}
@@ -0,0 +1,241 @@
+// ===== Signature recovery =====
+//
+// Inverse of the signing NIFs: rebuild the digest a signature should be over and recover the
+// address that produced it. Useful to check which key signed an action (e.g. when the exchange
+// answers "User or API Wallet does not exist") without contacting the API.
+
+use std::str::FromStr;
+
+use alloy::primitives::{Address, Signature as AlloySignature, B256, U256};
+use rustler::{Encoder, Env, NifResult, Term};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    chain, hash_json_value_with_exp, l1_agent_payload, multi_sig_payload, parse_address, parse_optional_address, typed_data_digest,
+    ApproveAgent, ApproveBuilderFee, Eip712, Error, SendMultiSig, SpotSend, UsdSend, Withdraw3,
+};
+
+// Accepts `v` as 27/28 (as returned by the signing NIFs) or as a 0/1 parity.
+pub(crate) fn parse_signature(r: &str, s: &str, v: u64) -> Result<AlloySignature, Error> {
+    let r = U256::from_str(r).map_err(|e| Error::GenericParse(format!("invalid r: {e}")))?;
+    let s = U256::from_str(s).map_err(|e| Error::GenericParse(format!("invalid s: {e}")))?;
+    let parity = match v {
+        0 | 27 => false,
+        1 | 28 => true,
+        other => return Err(Error::GenericParse(format!("invalid v: {other}"))),
+    };
+    Ok(AlloySignature::new(r, s, parity))
+}
+
+pub(crate) fn recover_digest(sig: &AlloySignature, digest: &B256) -> Result<Address, Error> {
+    sig.recover_address_from_prehash(digest).map_err(|e| Error::SignatureFailure(e.to_string()))
+}
+
+// Recover the signer of an L1 action from the action JSON exactly as it was submitted.
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn recover_l1_action_signer<'a>(
+    env: Env<'a>,
+    action_json: String,
+    nonce: u64,
+    vault_address: Option<String>,
+    expires_after: Option<u64>,
+    is_mainnet: bool,
+    r: String,
+    s: String,
+    v: u64,
+) -> NifResult<Term<'a>> {
+    let value: JsonValue = serde_json::from_str(&action_json).map_err(|e| Error::JsonParse(e.to_string()))?;
+    let vault = parse_optional_address(vault_address)?;
+    let sig = parse_signature(&r, &s, v)?;
+
+    let cid = hash_json_value_with_exp(&value, nonce, vault, expires_after)?;
+    let digest = l1_agent_payload(cid, is_mainnet).eip712_signing_hash();
+    let address = recover_digest(&sig, &digest)?;
+
+    let mut map = rustler::types::map::map_new(env);
+    map = map
+        .map_put("address".encode(env), address.to_string().encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    map = map
+        .map_put("connection_id".encode(env), format!("{:#x}", cid).encode(env))
+        .map_err(|_| rustler::Error::Term(Box::new("failed to encode map value")))?;
+    Ok(map)
+}
+
+fn recover_payload<T: Eip712>(payload: &T, r: &str, s: &str, v: u64) -> NifResult<String> {
+    let sig = parse_signature(r, s, v)?;
+    recover_digest(&sig, &payload.eip712_signing_hash())
+        .map(|address| address.to_string())
+        .map_err(rustler::Error::from)
+}
+
+// Malformed arguments are errors; a well-formed signature by another key is `false`.
+fn verify_payload<T: Eip712>(payload: &T, address: &str, r: &str, s: &str, v: u64) -> NifResult<bool> {
+    let expected = parse_address("address", address)?;
+    let sig = parse_signature(r, s, v)?;
+    Ok(recover_digest(&sig, &payload.eip712_signing_hash()).is_ok_and(|signer| signer == expected))
+}
+
+// ===== User-signed actions =====
+//
+// Same arguments as the matching `sign_*` NIF followed by `r`, `s`, `v`. `recover_*` returns the
+// checksummed signer address; `verify_*` takes the expected address first and returns a boolean.
+
+fn usd_send(destination: String, amount: String, time: u64, is_mainnet: bool) -> UsdSend {
+    let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
+    UsdSend { signature_chain_id, hyperliquid_chain, destination, amount, time }
+}
+
+#[rustler::nif]
+fn recover_usd_send(destination: String, amount: String, time: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<String> {
+    recover_payload(&usd_send(destination, amount, time, is_mainnet), &r, &s, v)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn verify_usd_send(address: String, destination: String, amount: String, time: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<bool> {
+    verify_payload(&usd_send(destination, amount, time, is_mainnet), &address, &r, &s, v)
+}
+
+fn withdraw3(destination: String, amount: String, time: u64, is_mainnet: bool) -> Withdraw3 {
+    let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
+    Withdraw3 { signature_chain_id, hyperliquid_chain, destination, amount, time }
+}
+
+#[rustler::nif]
+fn recover_withdraw3(destination: String, amount: String, time: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<String> {
+    recover_payload(&withdraw3(destination, amount, time, is_mainnet), &r, &s, v)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn verify_withdraw3(address: String, destination: String, amount: String, time: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<bool> {
+    verify_payload(&withdraw3(destination, amount, time, is_mainnet), &address, &r, &s, v)
+}
+
+fn spot_send(destination: String, token: String, amount: String, time: u64, is_mainnet: bool) -> SpotSend {
+    let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
+    SpotSend { signature_chain_id, hyperliquid_chain, destination, token, amount, time }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn recover_spot_send(destination: String, token: String, amount: String, time: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<String> {
+    recover_payload(&spot_send(destination, token, amount, time, is_mainnet), &r, &s, v)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn verify_spot_send(address: String, destination: String, token: String, amount: String, time: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<bool> {
+    verify_payload(&spot_send(destination, token, amount, time, is_mainnet), &address, &r, &s, v)
+}
+
+fn approve_builder_fee(builder: &str, max_fee_rate: String, nonce: u64, is_mainnet: bool) -> NifResult<ApproveBuilderFee> {
+    let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
+    let builder = parse_address("builder", builder)?;
+    Ok(ApproveBuilderFee { signature_chain_id, hyperliquid_chain, builder, max_fee_rate, nonce })
+}
+
+#[rustler::nif]
+fn recover_approve_builder_fee(builder: String, max_fee_rate: String, nonce: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<String> {
+    recover_payload(&approve_builder_fee(&builder, max_fee_rate, nonce, is_mainnet)?, &r, &s, v)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn verify_approve_builder_fee(address: String, builder: String, max_fee_rate: String, nonce: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<bool> {
+    verify_payload(&approve_builder_fee(&builder, max_fee_rate, nonce, is_mainnet)?, &address, &r, &s, v)
+}
+
+fn approve_agent(agent_address: &str, agent_name: Option<String>, nonce: u64, is_mainnet: bool) -> NifResult<ApproveAgent> {
+    let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
+    let agent_address = parse_address("agent_address", agent_address)?;
+    Ok(ApproveAgent { signature_chain_id, hyperliquid_chain, agent_address, agent_name, nonce })
+}
+
+#[rustler::nif]
+fn recover_approve_agent(agent_address: String, agent_name: Option<String>, nonce: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<String> {
+    recover_payload(&approve_agent(&agent_address, agent_name, nonce, is_mainnet)?, &r, &s, v)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn verify_approve_agent(address: String, agent_address: String, agent_name: Option<String>, nonce: u64, is_mainnet: bool, r: String, s: String, v: u64) -> NifResult<bool> {
+    verify_payload(&approve_agent(&agent_address, agent_name, nonce, is_mainnet)?, &address, &r, &s, v)
+}
+
+fn multi_sig(action_json: &str, nonce: u64, is_mainnet: bool, vault_address: Option<String>, expires_after: Option<u64>) -> NifResult<SendMultiSig> {
+    let value: JsonValue = serde_json::from_str(action_json).map_err(|e| Error::JsonParse(e.to_string()))?;
+    let vault = parse_optional_address(vault_address)?;
+    multi_sig_payload(&value, nonce, is_mainnet, vault, expires_after)
+}
+
+// Counterpart of `sign_multi_sig_action_ex`.
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn recover_multi_sig_action(
+    action_json: String,
+    nonce: u64,
+    is_mainnet: bool,
+    vault_address: Option<String>,
+    expires_after: Option<u64>,
+    r: String,
+    s: String,
+    v: u64,
+) -> NifResult<String> {
+    recover_payload(&multi_sig(&action_json, nonce, is_mainnet, vault_address, expires_after)?, &r, &s, v)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn verify_multi_sig_action(
+    address: String,
+    action_json: String,
+    nonce: u64,
+    is_mainnet: bool,
+    vault_address: Option<String>,
+    expires_after: Option<u64>,
+    r: String,
+    s: String,
+    v: u64,
+) -> NifResult<bool> {
+    verify_payload(&multi_sig(&action_json, nonce, is_mainnet, vault_address, expires_after)?, &address, &r, &s, v)
+}
+
+// ===== Generic typed data =====
+
+#[rustler::nif]
+fn recover_typed_data(
+    domain_json: String,
+    types_json: String,
+    message_json: String,
+    primary_type: String,
+    r: String,
+    s: String,
+    v: u64,
+) -> NifResult<String> {
+    let (_, digest) = typed_data_digest(domain_json, types_json, message_json, primary_type)?;
+    let sig = parse_signature(&r, &s, v)?;
+    recover_digest(&sig, &digest)
+        .map(|address| address.to_string())
+        .map_err(rustler::Error::from)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[rustler::nif]
+fn verify_typed_data(
+    address: String,
+    domain_json: String,
+    types_json: String,
+    message_json: String,
+    primary_type: String,
+    r: String,
+    s: String,
+    v: u64,
+) -> NifResult<bool> {
+    let expected = parse_address("address", &address)?;
+    let (_, digest) = typed_data_digest(domain_json, types_json, message_json, primary_type)?;
+    let sig = parse_signature(&r, &s, v)?;
+    Ok(recover_digest(&sig, &digest).is_ok_and(|signer| signer == expected))
+}
@@ -0,0 +1,97 @@
+// ===== eth_signTypedData_v4 remote signer =====
+//
+// Backend for custody services that expose `eth_signTypedData_v4` over HTTP JSON-RPC (Clef,
+// Web3Signer and similar). Every payload is sent as the full typed-data document rather than a
+// digest, so the custodian can display and policy-check what it signs. The digest is still
+// computed locally and the returned signature must recover to the configured address over it;
+// a signer that signs anything else is rejected.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use alloy::primitives::{Address, Signature as AlloySignature, B256};
+use rustler::{NifResult, ResourceArc};
+use serde_json::{json, Value as JsonValue};
+
+use crate::backend::SignerBackend;
+use crate::{parse_address, Error, WalletResource};
+
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct TypedDataSigner {
+    url: String,
+    address: Address,
+    timeout: Duration,
+    // Send the document as a JSON string (MetaMask convention) instead of an object.
+    stringify: bool,
+}
+
+impl TypedDataSigner {
+    pub fn new(url: impl Into<String>, address: Address, timeout: Duration, stringify: bool) -> Self {
+        TypedDataSigner { url: url.into(), address, timeout, stringify }
+    }
+
+    fn request_signature(&self, typed_data: JsonValue) -> Result<AlloySignature, Error> {
+        let document = if self.stringify { JsonValue::from(typed_data.to_string()) } else { typed_data };
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_signTypedData_v4",
+            "params": [self.address.to_string(), document],
+        });
+        let response: JsonValue = ureq::post(&self.url)
+            .timeout(self.timeout)
+            .send_json(request)
+            .map_err(|e| Error::Backend(format!("eth_signTypedData_v4 request failed: {e}")))?
+            .into_json()
+            .map_err(|e| Error::Backend(format!("eth_signTypedData_v4 invalid response: {e}")))?;
+        if let Some(err) = response.get("error") {
+            return Err(Error::Backend(format!("eth_signTypedData_v4 failed: {err}")));
+        }
+        let sig_hex = response
+            .get("result")
+            .and_then(JsonValue::as_str)
+            .ok_or_else(|| Error::Backend("eth_signTypedData_v4: missing result".to_string()))?;
+        AlloySignature::from_str(sig_hex)
+            .map_err(|e| Error::Backend(format!("eth_signTypedData_v4: invalid signature: {e}")))
+    }
+}
+
+impl SignerBackend for TypedDataSigner {
+    fn address(&self) -> Address { self.address }
+
+    fn sign_hash(&self, _hash: &B256) -> Result<AlloySignature, Error> {
+        Err(Error::Backend("eth_signTypedData_v4 signers cannot sign raw digests".to_string()))
+    }
+
+    fn sign_typed(&self, digest: &B256, typed_data: &dyn Fn() -> JsonValue) -> Result<AlloySignature, Error> {
+        let sig = self.request_signature(typed_data())?;
+        let recovered = sig
+            .recover_address_from_prehash(digest)
+            .map_err(|e| Error::Backend(format!("eth_signTypedData_v4: unrecoverable signature: {e}")))?;
+        if recovered != self.address {
+            return Err(Error::Backend(format!(
+                "eth_signTypedData_v4: signature recovers to {recovered}, expected {}",
+                self.address
+            )));
+        }
+        Ok(sig)
+    }
+}
+
+// Return a wallet handle that signs every EIP-712 payload (user-signed actions and the L1
+// `Agent` struct) through `eth_signTypedData_v4` at `url` for `address`. Like the signing NIFs
+// that use the handle, this runs on a dirty IO scheduler: each signature is a blocking HTTP call
+// of up to `timeout_ms`.
+#[rustler::nif(schedule = "DirtyIo")]
+fn connect_typed_data_signer(
+    url: String,
+    address: String,
+    timeout_ms: Option<u64>,
+    stringify: bool,
+) -> NifResult<ResourceArc<WalletResource>> {
+    let address = parse_address("address", &address)?;
+    let timeout = timeout_ms.map(Duration::from_millis).unwrap_or(DEFAULT_HTTP_TIMEOUT);
+    let signer = TypedDataSigner::new(url, address, timeout, stringify);
+    Ok(ResourceArc::new(WalletResource::new(Box::new(signer))))
+}
@@ -0,0 +1,168 @@
+// ===== Tick and lot size rules =====
+//
+// Prices and sizes must fit the asset's precision or the exchange rejects the order:
+//
+//   size:  at most szDecimals decimals
+//   price: at most 5 significant figures and at most MAX_DECIMALS - szDecimals decimals, where
+//          MAX_DECIMALS is 6 for perps and 8 for spot; integer prices are always allowed
+//
+// Each value is either validated (an error when it does not fit) or truncated toward zero, which
+// may leave "0". Arithmetic is exact on the decimal digits; nothing passes through f64.
+//
+//   price("50000.123456", 5, false, Mode::Truncate)    == "50000"
+//   price("0.0000123456789", 0, true, Mode::Truncate)  == "0.00001234"
+//   size("0.0001", 3, Mode::Truncate)                  == "0"
+//   size("1.23456789", 5, Mode::Validate)              -> error, more than 5 decimals
+//
+// Elixir pushes the szDecimals table from `Hyperliquid.Cache` with `set_sz_decimals/2`. When it
+// is pushed with `enforce = true`, signing an `order` or `batchModify` whose prices or sizes do
+// not fit is refused, as is one for an asset missing from the table.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use rustler::{Atom, NifResult};
+
+use crate::wire::{format_parts, parse_decimal, Parts};
+use crate::{Actions, Error, OrderRequest};
+
+rustler::atoms! { ok, validate, truncate }
+
+const MAX_SIG_FIGS: i64 = 5;
+const MAX_PERP_DECIMALS: u32 = 6;
+const MAX_SPOT_DECIMALS: u32 = 8;
+
+static TABLE: RwLock<Option<Arc<Table>>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode { Validate, Truncate }
+
+#[derive(Debug, Clone)]
+pub struct Table { pub sz_decimals: HashMap<u32, u32>, pub enforce: bool }
+
+fn tick_err(msg: String) -> Error {
+    Error::GenericParse(msg)
+}
+
+// Spot assets are numbered 10000..100000; everything else (including builder perps) is a perp.
+pub fn is_spot(asset: u32) -> bool {
+    (10_000..100_000).contains(&asset)
+}
+
+pub fn max_price_decimals(sz_decimals: u32, is_spot: bool) -> u32 {
+    let max = if is_spot { MAX_SPOT_DECIMALS } else { MAX_PERP_DECIMALS };
+    max.saturating_sub(sz_decimals)
+}
+
+// Parse a non-negative value into integer digits without leading zeros and fraction digits
+// without trailing zeros.
+fn digits(what: &str, value: &str) -> Result<(String, String), Error> {
+    let Parts { negative, int_digits, frac_digits } = parse_decimal(value)?;
+    let int_digits = int_digits.trim_start_matches('0').to_string();
+    let frac_digits = frac_digits.trim_end_matches('0').to_string();
+    if negative && !(int_digits.is_empty() && frac_digits.is_empty()) {
+        return Err(tick_err(format!("{what} {value:?} must not be negative")));
+    }
+    Ok((int_digits, frac_digits))
+}
+
+// Keep at most `allowed` decimals, or fail with `reason` in validate mode.
+fn fit(what: &str, value: &str, int_digits: &str, frac_digits: &str, allowed: u32, mode: Mode, reason: String) -> Result<String, Error> {
+    let allowed = allowed as usize;
+    if frac_digits.len() <= allowed {
+        return Ok(format_parts(false, int_digits, frac_digits));
+    }
+    if mode == Mode::Validate {
+        return Err(tick_err(format!("{what} {value:?} {reason}")));
+    }
+    Ok(format_parts(false, int_digits, &frac_digits[..allowed]))
+}
+
+pub fn price(value: &str, sz_decimals: u32, is_spot: bool, mode: Mode) -> Result<String, Error> {
+    let (int_digits, frac_digits) = digits("price", value)?;
+    let max_decimals = max_price_decimals(sz_decimals, is_spot);
+    // Position of the most significant digit: 0 for 1..10, -1 for 0.1..1 and so on.
+    let magnitude = match int_digits.len() {
+        0 => -(frac_digits.bytes().take_while(|b| *b == b'0').count() as i64) - 1,
+        n => n as i64 - 1,
+    };
+    let sig_fig_decimals = (MAX_SIG_FIGS - 1 - magnitude).max(0) as u32;
+    let reason = if frac_digits.len() > max_decimals as usize {
+        format!("has more than {max_decimals} decimals")
+    } else {
+        format!("has more than {MAX_SIG_FIGS} significant figures")
+    };
+    fit("price", value, &int_digits, &frac_digits, max_decimals.min(sig_fig_decimals), mode, reason)
+}
+
+pub fn size(value: &str, sz_decimals: u32, mode: Mode) -> Result<String, Error> {
+    let (int_digits, frac_digits) = digits("size", value)?;
+    fit("size", value, &int_digits, &frac_digits, sz_decimals, mode, format!("has more than {sz_decimals} decimals"))
+}
+
+fn check_order(table: &Table, index: usize, order: &OrderRequest) -> Result<(), Error> {
+    let sz_decimals = *table
+        .sz_decimals
+        .get(&order.asset)
+        .ok_or_else(|| tick_err(format!("order {index}: no szDecimals known for asset {}", order.asset)))?;
+    let spot = is_spot(order.asset);
+    let in_order = |e: Error| match e {
+        Error::GenericParse(msg) => tick_err(format!("order {index}: {msg} for asset {}", order.asset)),
+        other => other,
+    };
+    price(&order.limit_px, sz_decimals, spot, Mode::Validate).map_err(in_order)?;
+    size(&order.sz, sz_decimals, Mode::Validate).map_err(in_order)?;
+    if let crate::Order::Trigger(trigger) = &order.order_type {
+        price(&trigger.trigger_px, sz_decimals, spot, Mode::Validate).map_err(in_order)?;
+    }
+    Ok(())
+}
+
+// Check every order of an `order` or `batchModify` action against the pushed table; called by
+// the signing NIFs before they sign. A no-op unless the table was pushed with `enforce = true`.
+pub(crate) fn enforce(action: &Actions) -> NifResult<()> {
+    let table = TABLE.read().unwrap_or_else(|e| e.into_inner()).clone();
+    let Some(table) = table.filter(|t| t.enforce) else { return Ok(()) };
+    let orders: Vec<&OrderRequest> = match action {
+        Actions::Order(bulk) => bulk.orders.iter().collect(),
+        Actions::BatchModify(bulk) => bulk.modifies.iter().map(|m| &m.order).collect(),
+        _ => return Ok(()),
+    };
+    for (index, order) in orders.into_iter().enumerate() {
+        check_order(&table, index, order)?;
+    }
+    Ok(())
+}
+
+fn decode_mode(mode: Atom) -> NifResult<Mode> {
+    if mode == validate() {
+        Ok(Mode::Validate)
+    } else if mode == truncate() {
+        Ok(Mode::Truncate)
+    } else {
+        Err(rustler::Error::Term(Box::new("mode must be :validate or :truncate")))
+    }
+}
+
+#[rustler::nif(name = "format_price")]
+fn nif_format_price(value: String, sz_decimals: u32, is_spot: bool, mode: Atom) -> NifResult<String> {
+    price(&value, sz_decimals, is_spot, decode_mode(mode)?).map_err(rustler::Error::from)
+}
+
+#[rustler::nif(name = "format_size")]
+fn nif_format_size(value: String, sz_decimals: u32, mode: Atom) -> NifResult<String> {
+    size(&value, sz_decimals, decode_mode(mode)?).map_err(rustler::Error::from)
+}
+
+// Replace the process-wide asset -> szDecimals table.
+#[rustler::nif]
+fn set_sz_decimals(sz_decimals: HashMap<u32, u32>, enforce: bool) -> Atom {
+    *TABLE.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(Table { sz_decimals, enforce }));
+    ok()
+}
+
+#[rustler::nif]
+fn clear_sz_decimals() -> Atom {
+    *TABLE.write().unwrap_or_else(|e| e.into_inner()) = None;
+    ok()
+}
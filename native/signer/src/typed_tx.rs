@@ -0,0 +1,120 @@
+// ===== Declarative HyperliquidTransaction:* structs =====
+//
+// Each user-signed EIP-712 struct is declared once with `hyperliquid_tx!`: its primary type and
+// its members in type-string order, each as a Rust field, a Rust type and the EIP-712 member
+// name. The solidity type comes from the Rust type, and the type string, struct hash and
+// eth_signTypedData_v4 message are all derived from the declaration:
+//
+//   hyperliquid_tx! {
+//       ApproveBuilderFee = "HyperliquidTransaction:ApproveBuilderFee" {
+//           hyperliquid_chain: String => "hyperliquidChain",
+//           max_fee_rate: String => "maxFeeRate",
+//           builder: Address => "builder",
+//           nonce: u64 => "nonce",
+//       }
+//   }
+//
+// gives `pub struct ApproveBuilderFee { pub signature_chain_id: u64, pub hyperliquid_chain, .. }`
+// signed over "HyperliquidTransaction:ApproveBuilderFee(string hyperliquidChain,string
+// maxFeeRate,address builder,uint64 nonce)" in the `HyperliquidSignTransaction` domain for
+// `signature_chain_id`. Member order is signature-relevant: declare it exactly as the reference
+// SDK's type string lists it.
+
+use alloy::primitives::{keccak256, Address, B256, U256};
+use serde_json::Value as JsonValue;
+
+// A Rust type usable as a struct member: its solidity type, its 32-byte encoding in
+// `encodeData` and its value in a typed-data message.
+pub trait TxField {
+    const SOL_TYPE: &'static str;
+    fn encode_word(&self) -> B256;
+    fn to_json(&self) -> JsonValue;
+}
+
+impl TxField for String {
+    const SOL_TYPE: &'static str = "string";
+    fn encode_word(&self) -> B256 { keccak256(self.as_bytes()) }
+    fn to_json(&self) -> JsonValue { JsonValue::from(self.as_str()) }
+}
+
+// An absent string member is signed as "".
+impl TxField for Option<String> {
+    const SOL_TYPE: &'static str = "string";
+    fn encode_word(&self) -> B256 { keccak256(self.as_deref().unwrap_or("").as_bytes()) }
+    fn to_json(&self) -> JsonValue { JsonValue::from(self.as_deref().unwrap_or("")) }
+}
+
+impl TxField for Address {
+    const SOL_TYPE: &'static str = "address";
+    fn encode_word(&self) -> B256 { self.into_word() }
+    fn to_json(&self) -> JsonValue { JsonValue::from(self.to_string()) }
+}
+
+impl TxField for u64 {
+    const SOL_TYPE: &'static str = "uint64";
+    fn encode_word(&self) -> B256 { B256::from(U256::from(*self)) }
+    fn to_json(&self) -> JsonValue { JsonValue::from(*self) }
+}
+
+impl TxField for bool {
+    const SOL_TYPE: &'static str = "bool";
+    fn encode_word(&self) -> B256 { B256::with_last_byte(u8::from(*self)) }
+    fn to_json(&self) -> JsonValue { JsonValue::from(*self) }
+}
+
+impl TxField for B256 {
+    const SOL_TYPE: &'static str = "bytes32";
+    fn encode_word(&self) -> B256 { *self }
+    fn to_json(&self) -> JsonValue { JsonValue::from(format!("{self:#x}")) }
+}
+
+// "Primary(type1 name1,type2 name2,...)"
+pub fn type_string(primary_type: &str, fields: &[(&str, &str)]) -> String {
+    let members: Vec<String> = fields.iter().map(|(name, ty)| format!("{ty} {name}")).collect();
+    format!("{primary_type}({})", members.join(","))
+}
+
+// keccak256(typeHash || encodeData) over pre-encoded member words.
+pub fn struct_hash(primary_type: &str, fields: &[(&str, &str)], words: &[B256]) -> B256 {
+    let mut encoded = Vec::with_capacity(32 * (words.len() + 1));
+    encoded.extend_from_slice(keccak256(type_string(primary_type, fields)).as_slice());
+    for word in words {
+        encoded.extend_from_slice(word.as_slice());
+    }
+    keccak256(encoded)
+}
+
+macro_rules! hyperliquid_tx {
+    ($(#[$meta:meta])* $name:ident = $primary:literal { $($field:ident: $ty:ty => $member:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Debug, Clone)]
+        #[serde(rename_all = "camelCase")]
+        pub struct $name { pub signature_chain_id: u64, $(pub $field: $ty),+ }
+
+        impl $name {
+            pub const PRIMARY_TYPE: &'static str = $primary;
+            // Members in type-string order, as (name, solidity type).
+            pub const FIELDS: &'static [(&'static str, &'static str)] =
+                &[$(($member, <$ty as $crate::typed_tx::TxField>::SOL_TYPE)),+];
+
+            pub fn type_string() -> String {
+                $crate::typed_tx::type_string(Self::PRIMARY_TYPE, Self::FIELDS)
+            }
+        }
+
+        impl $crate::Eip712 for $name {
+            fn domain(&self) -> ::alloy::dyn_abi::Eip712Domain { $crate::tx_domain(self.signature_chain_id) }
+            fn struct_hash(&self) -> ::alloy::primitives::B256 {
+                let words = [$($crate::typed_tx::TxField::encode_word(&self.$field)),+];
+                $crate::typed_tx::struct_hash(Self::PRIMARY_TYPE, Self::FIELDS, &words)
+            }
+            fn typed_data(&self) -> ::serde_json::Value {
+                let mut message = ::serde_json::Map::new();
+                $(message.insert($member.to_string(), $crate::typed_tx::TxField::to_json(&self.$field));)+
+                $crate::typed_data_document(&self.domain(), Self::PRIMARY_TYPE, Self::FIELDS, ::serde_json::Value::Object(message))
+            }
+        }
+    };
+}
+
+pub(crate) use hyperliquid_tx;
@@ -0,0 +1,175 @@
+// ===== Generic user-signed actions =====
+//
+// User-signed actions are signed as `HyperliquidTransaction:*` EIP-712 structs whose members are
+// the action's own fields, in a fixed order. The registry below maps each action type to its
+// `hyperliquid_tx!` struct; the EIP-712 types, message and digest are derived from it and the
+// action, so adding an action is one declaration rather than a hand-built domain/types/message
+// triple on the Elixir side.
+//
+//   Signer.sign_user_signed_action(wallet, %{type: "cDeposit", wei: 100_000_000, nonce: nonce}, true)
+//   #=> %{"r" => .., "s" => .., "v" => .., "signature" => .., "action_json" => ~s({"type":"cDeposit",...})}
+//
+// `hyperliquidChain` and `signatureChainId` are filled in from the network profile when the action does
+// not carry them; a `hyperliquidChain` for the other network is refused.
+//
+// Co-signers of a multi-sig account sign the same struct with two extra `address` members,
+// `payloadMultiSigUser` and `outerSigner`, right after `hyperliquidChain` (`UserSigned::multi_sig`).
+
+use alloy::dyn_abi::{Eip712Domain, TypedData};
+use alloy::primitives::{Address, B256};
+use rustler::{Encoder, Env, NifResult, Term};
+use serde_json::Value as JsonValue;
+
+use crate::encode::{put, term_to_json};
+use crate::explorer::chain_id;
+use crate::{
+    action_type, audit, chain, policy, sign_typed_data, signature_to_map, tx_domain, typed_data_document, Actions, ApproveAgent,
+    ApproveBuilderFee, CDeposit, CWithdraw, Eip712, Error, SendAsset, SendToEvmWithData, SpotSend, TokenDelegate,
+    UsdClassTransfer, UsdSend, UserSetAbstraction, WalletRef, Withdraw3,
+};
+
+pub struct UserSignedType {
+    pub action: &'static str,
+    pub primary_type: &'static str,
+    pub fields: &'static [(&'static str, &'static str)],
+}
+
+const fn tx(action: &'static str, primary_type: &'static str, fields: &'static [(&'static str, &'static str)]) -> UserSignedType {
+    UserSignedType { action, primary_type, fields }
+}
+
+// Action type -> `hyperliquid_tx!` struct. A new user-signed action is one `hyperliquid_tx!`
+// declaration in lib.rs plus one line here.
+pub const REGISTRY: &[UserSignedType] = &[
+    tx("usdSend", UsdSend::PRIMARY_TYPE, UsdSend::FIELDS),
+    tx("spotSend", SpotSend::PRIMARY_TYPE, SpotSend::FIELDS),
+    tx("withdraw3", Withdraw3::PRIMARY_TYPE, Withdraw3::FIELDS),
+    tx("usdClassTransfer", UsdClassTransfer::PRIMARY_TYPE, UsdClassTransfer::FIELDS),
+    tx("approveAgent", ApproveAgent::PRIMARY_TYPE, ApproveAgent::FIELDS),
+    tx("approveBuilderFee", ApproveBuilderFee::PRIMARY_TYPE, ApproveBuilderFee::FIELDS),
+    tx("sendAsset", SendAsset::PRIMARY_TYPE, SendAsset::FIELDS),
+    tx("sendToEvmWithData", SendToEvmWithData::PRIMARY_TYPE, SendToEvmWithData::FIELDS),
+    tx("cDeposit", CDeposit::PRIMARY_TYPE, CDeposit::FIELDS),
+    tx("cWithdraw", CWithdraw::PRIMARY_TYPE, CWithdraw::FIELDS),
+    tx("tokenDelegate", TokenDelegate::PRIMARY_TYPE, TokenDelegate::FIELDS),
+    tx("userSetAbstraction", UserSetAbstraction::PRIMARY_TYPE, UserSetAbstraction::FIELDS),
+];
+
+pub fn lookup(action: &str) -> Option<&'static UserSignedType> {
+    REGISTRY.iter().find(|t| t.action == action)
+}
+
+fn parse_err(msg: impl Into<String>) -> Error {
+    Error::GenericParse(msg.into())
+}
+
+// EIP-712 payload of a user-signed action, derived from its registry entry.
+#[derive(Debug, Clone)]
+pub struct UserSigned {
+    pub signature_chain_id: u64,
+    pub primary_type: &'static str,
+    pub fields: Vec<(&'static str, &'static str)>,
+    pub message: JsonValue,
+    struct_hash: B256,
+}
+
+impl UserSigned {
+    // Build the payload from a wire-form action (`type`, `signatureChainId` and the struct fields).
+    // An absent or null `string` member is signed as "" (e.g. an unnamed agent).
+    pub fn from_action(action: &JsonValue) -> Result<UserSigned, Error> {
+        UserSigned::build(action, None)
+    }
+
+    // Payload a co-signer of `multi_sig_user` signs for the same action.
+    pub fn multi_sig(action: &JsonValue, multi_sig_user: Address, outer_signer: Address) -> Result<UserSigned, Error> {
+        UserSigned::build(action, Some((multi_sig_user, outer_signer)))
+    }
+
+    fn build(action: &JsonValue, multi_sig: Option<(Address, Address)>) -> Result<UserSigned, Error> {
+        let action_type = action.get("type").and_then(JsonValue::as_str).ok_or_else(|| parse_err("missing string field type"))?;
+        let tx = lookup(action_type).ok_or_else(|| parse_err(format!("unsupported user-signed action type {action_type}")))?;
+        let mut fields = Vec::with_capacity(tx.fields.len() + 2);
+        let mut message = serde_json::Map::new();
+        for &(name, ty) in tx.fields {
+            let value = match action.get(name).filter(|v| !v.is_null()) {
+                Some(value) => value.clone(),
+                None if ty == "string" => JsonValue::from(""),
+                None => return Err(parse_err(format!("{action_type} is missing field {name}"))),
+            };
+            fields.push((name, ty));
+            message.insert(name.to_string(), value);
+            if let (Some((multi_sig_user, outer_signer)), "hyperliquidChain") = (multi_sig, name) {
+                fields.push(("payloadMultiSigUser", "address"));
+                fields.push(("outerSigner", "address"));
+                message.insert("payloadMultiSigUser".to_string(), JsonValue::from(format!("{multi_sig_user:#x}")));
+                message.insert("outerSigner".to_string(), JsonValue::from(format!("{outer_signer:#x}")));
+            }
+        }
+        let signature_chain_id = chain_id(action)?;
+        let mut payload = UserSigned {
+            signature_chain_id,
+            primary_type: tx.primary_type,
+            fields,
+            message: JsonValue::Object(message),
+            struct_hash: B256::ZERO,
+        };
+        let typed: TypedData = serde_json::from_value(payload.typed_data()).map_err(|e| parse_err(format!("typed data error: {e}")))?;
+        payload.struct_hash = typed.hash_struct().map_err(|e| parse_err(format!("eip712 encode error: {e}")))?;
+        Ok(payload)
+    }
+}
+
+impl Eip712 for UserSigned {
+    fn domain(&self) -> Eip712Domain { tx_domain(self.signature_chain_id) }
+    fn struct_hash(&self) -> B256 { self.struct_hash }
+    fn typed_data(&self) -> JsonValue {
+        typed_data_document(&self.domain(), self.primary_type, &self.fields, self.message.clone())
+    }
+}
+
+// Fill in the network fields and validate the action against the typed model; returns the
+// action in canonical wire form.
+pub(crate) fn complete_action(mut value: JsonValue, is_mainnet: bool) -> Result<Actions, Error> {
+    let (signature_chain_id, hyperliquid_chain) = chain(is_mainnet);
+    let object = value.as_object_mut().ok_or_else(|| parse_err("action must be a map"))?;
+    match object.get("hyperliquidChain") {
+        None | Some(JsonValue::Null) => {
+            object.insert("hyperliquidChain".to_string(), JsonValue::from(hyperliquid_chain));
+        }
+        Some(chain) if chain.as_str() == Some(hyperliquid_chain.as_str()) => {}
+        Some(chain) => return Err(parse_err(format!("hyperliquidChain {chain} does not match the {hyperliquid_chain} network"))),
+    }
+    if object.get("signatureChainId").is_none_or(JsonValue::is_null) {
+        object.insert("signatureChainId".to_string(), JsonValue::from(format!("{signature_chain_id:#x}")));
+    }
+    let action: Actions = serde_json::from_value(value).map_err(|e| Error::JsonParse(e.to_string()))?;
+    if !action.is_user_signed() {
+        return Err(parse_err(format!("{} is not a user-signed action", action_type(&action))));
+    }
+    Ok(action)
+}
+
+// The `nonce` or `time` member a user-signed action is keyed by.
+pub(crate) fn action_nonce(action: &JsonValue) -> Option<u64> {
+    ["nonce", "time"].iter().find_map(|k| action.get(k).and_then(JsonValue::as_u64))
+}
+
+// Sign any registered user-signed action given as an Elixir term (map, keyword list or
+// `Jason.OrderedObject`). The result also carries `action_json`, the canonical body to send.
+#[rustler::nif(schedule = "DirtyIo")]
+fn sign_user_signed_action<'a>(env: Env<'a>, wallet: WalletRef, action: Term<'a>, is_mainnet: bool) -> NifResult<Term<'a>> {
+    let value = term_to_json(action)?;
+    let action = complete_action(value, is_mainnet)?;
+    let action_type = action_type(&action);
+    let canonical = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+
+    policy::enforce(policy::Request::action_json(&canonical, is_mainnet))?;
+
+    let payload = UserSigned::from_action(&canonical)?;
+    let nonce = action_nonce(&canonical);
+    let audit = audit::Context { action: Some(action_type), nonce, ..Default::default() };
+    let sig = sign_typed_data(&payload, &*wallet, audit)?;
+
+    let map = signature_to_map(env, sig, None)?;
+    put(map, "action_json", canonical.to_string().encode(env))
+}
@@ -0,0 +1,163 @@
+// ===== Wire format for prices and sizes =====
+//
+// Prices and sizes are signed as decimal strings, so two spellings of the same number hash
+// differently. Everything is normalized to the reference SDK form: at most 8 decimals, no
+// trailing zeros, no trailing ".", and "0" rather than "-0".
+//
+//   float_to_wire(0.1 + 0.2)      == "0.3"
+//   decimal_to_wire("1.0e-5")     == "0.00001"
+//   decimal_to_wire("100.500")    == "100.5"
+//   decimal_to_wire("0.123456789") -> error, would lose precision
+//
+// Like the reference `float_to_wire`, a value is rejected when rounding it to 8 decimals moves it
+// by 1e-12 or more. Strings are handled with exact decimal arithmetic and never pass through f64.
+
+use rustler::NifResult;
+use serde::{Deserialize, Deserializer};
+
+use crate::Error;
+
+const DECIMALS: usize = 8;
+// Rounding may move the value by less than 1e-12, i.e. 1e-4 of the last kept decimal.
+const TOLERANCE_DIGITS: usize = 4;
+const MAX_EXPONENT: i32 = 64;
+// 2^53: past this, a float no longer holds every integer exactly.
+const MAX_EXACT_FLOAT: f64 = 9_007_199_254_740_992.0;
+
+fn wire_err(value: &str, msg: &str) -> Error {
+    Error::GenericParse(format!("{value:?} {msg}"))
+}
+
+// Reference SDK algorithm: format with 8 decimals, reject if that changed the value.
+pub fn float_to_wire(x: f64) -> Result<String, Error> {
+    if !x.is_finite() {
+        return Err(wire_err(&x.to_string(), "is not a finite number"));
+    }
+    let rounded = format!("{x:.DECIMALS$}");
+    let back: f64 = rounded.parse().map_err(|_| wire_err(&rounded, "is not a decimal number"))?;
+    if (back - x).abs() >= 1e-12 {
+        return Err(wire_err(&x.to_string(), "cannot be represented with 8 decimals"));
+    }
+    decimal_to_wire(&rounded)
+}
+
+// A decimal split into sign, integer digits and fraction digits, with any exponent applied.
+pub(crate) struct Parts { pub negative: bool, pub int_digits: String, pub frac_digits: String }
+
+// Parse a decimal string (optionally signed, optionally with an exponent) without rounding.
+pub(crate) fn parse_decimal(value: &str) -> Result<Parts, Error> {
+    let s = value.trim();
+    let (negative, unsigned) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(i) => {
+            let exp = unsigned[i + 1..].parse::<i32>().map_err(|_| wire_err(value, "has an invalid exponent"))?;
+            if exp.abs() > MAX_EXPONENT {
+                return Err(wire_err(value, "has an exponent out of range"));
+            }
+            (&unsigned[..i], exp)
+        }
+        None => (unsigned, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let all_digits = |p: &str| p.bytes().all(|b| b.is_ascii_digit());
+    if int_part.len() + frac_part.len() == 0 || !all_digits(int_part) || !all_digits(frac_part) {
+        return Err(wire_err(value, "is not a decimal number"));
+    }
+
+    // Move the decimal point by the exponent: `digits` with `point` digits before it.
+    let mut digits = format!("{int_part}{frac_part}");
+    let mut point = int_part.len() as i64 + exponent as i64;
+    if point < 0 {
+        digits.insert_str(0, &"0".repeat(point.unsigned_abs() as usize));
+        point = 0;
+    }
+    let point = point as usize;
+    if point > digits.len() {
+        digits.push_str(&"0".repeat(point - digits.len()));
+    }
+    let (int_digits, frac_digits) = digits.split_at(point);
+    Ok(Parts { negative, int_digits: int_digits.to_string(), frac_digits: frac_digits.to_string() })
+}
+
+// Write parts in wire form: no leading or trailing zeros, no "-0".
+pub(crate) fn format_parts(negative: bool, int_digits: &str, frac_digits: &str) -> String {
+    let int_digits = match int_digits.trim_start_matches('0') {
+        "" => "0",
+        trimmed => trimmed,
+    };
+    let frac_digits = frac_digits.trim_end_matches('0');
+    let mut out = String::new();
+    if negative && (int_digits != "0" || !frac_digits.is_empty()) {
+        out.push('-');
+    }
+    out.push_str(int_digits);
+    if !frac_digits.is_empty() {
+        out.push('.');
+        out.push_str(frac_digits);
+    }
+    out
+}
+
+// Normalize a decimal string (optionally signed, optionally with an exponent) exactly.
+pub fn decimal_to_wire(value: &str) -> Result<String, Error> {
+    let Parts { negative, int_digits, frac_digits } = parse_decimal(value)?;
+    let keep = frac_digits.len().min(DECIMALS);
+    let (kept, tail) = frac_digits.split_at(keep);
+    let mut fixed = format!("{int_digits}{kept}{}", "0".repeat(DECIMALS - keep));
+    if tail.bytes().any(|b| b != b'0') {
+        let lead = |d: u8| tail.len() >= TOLERANCE_DIGITS && tail.bytes().take(TOLERANCE_DIGITS).all(|b| b == d);
+        if lead(b'9') && tail.bytes().skip(TOLERANCE_DIGITS).any(|b| b != b'0') {
+            fixed = increment(&fixed);
+        } else if !lead(b'0') {
+            return Err(wire_err(value, "cannot be represented with 8 decimals"));
+        }
+    }
+
+    let (int_digits, frac_digits) = fixed.split_at(fixed.len() - DECIMALS);
+    Ok(format_parts(negative, int_digits, frac_digits))
+}
+
+// Add one to a string of decimal digits.
+fn increment(digits: &str) -> String {
+    let mut bytes = digits.as_bytes().to_vec();
+    for b in bytes.iter_mut().rev() {
+        if *b == b'9' {
+            *b = b'0';
+        } else {
+            *b += 1;
+            return String::from_utf8(bytes).unwrap_or_default();
+        }
+    }
+    format!("1{}", String::from_utf8(bytes).unwrap_or_default())
+}
+
+// Serde hook for price and size fields: accepts a decimal string or a JSON number and stores the
+// wire form, so every typed action is hashed over normalized values. JSON integers beyond u64
+// arrive as floats and, like any float past 2^53, are refused rather than silently rounded.
+pub fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw { Text(String), Int(i64), UInt(u64), Float(f64) }
+    let wire = match Raw::deserialize(deserializer)? {
+        Raw::Text(s) => decimal_to_wire(&s),
+        Raw::Int(n) => decimal_to_wire(&n.to_string()),
+        Raw::UInt(n) => decimal_to_wire(&n.to_string()),
+        Raw::Float(f) if f.abs() >= MAX_EXACT_FLOAT => Err(wire_err(&f.to_string(), "is too large to be represented exactly")),
+        Raw::Float(f) => float_to_wire(f),
+    };
+    wire.map_err(serde::de::Error::custom)
+}
+
+#[rustler::nif(name = "float_to_wire")]
+fn nif_float_to_wire(value: f64) -> NifResult<String> {
+    float_to_wire(value).map_err(rustler::Error::from)
+}
+
+#[rustler::nif(name = "decimal_to_wire")]
+fn nif_decimal_to_wire(value: String) -> NifResult<String> {
+    decimal_to_wire(&value).map_err(rustler::Error::from)
+}
@@ -0,0 +1,67 @@
+use std::process::Command;
+use std::str::FromStr;
+
+use alloy::primitives::{keccak256, Address, B256};
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use signer_nif::audit::{self, AuditLog, Context};
+
+const KEY: &str = "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff";
+
+fn write_log(path: &std::path::Path, count: u64) {
+    let signer = PrivateKeySigner::from_str(KEY).unwrap();
+    let mut log = AuditLog::open(path).unwrap();
+    for nonce in 0..count {
+        let digest = keccak256(nonce.to_be_bytes());
+        let sig = signer.sign_hash_sync(&digest).unwrap();
+        let ctx = Context { action: Some("order".to_string()), connection_id: Some(B256::repeat_byte(7)), nonce: Some(nonce), vault_address: None };
+        log.append(ctx, digest, signer.address(), &sig).unwrap();
+    }
+}
+
+fn temp_log(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("hl-audit-{}-{name}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn chain_survives_reopen_and_verifies() {
+    let path = temp_log("reopen");
+    write_log(&path, 2);
+    write_log(&path, 1);
+
+    let summary = audit::verify(&path).unwrap();
+    assert_eq!(summary.records, 3);
+    let last: serde_json::Value = serde_json::from_str(std::fs::read_to_string(&path).unwrap().lines().last().unwrap()).unwrap();
+    assert_eq!(format!("{:#x}", summary.head), last["hash"]);
+    assert_eq!(last["seq"], 2);
+    assert_eq!(last["signer"].as_str().map(|s| Address::from_str(s).unwrap()), Some(PrivateKeySigner::from_str(KEY).unwrap().address()));
+}
+
+#[test]
+fn detects_edited_and_deleted_records() {
+    let path = temp_log("tamper");
+    write_log(&path, 3);
+    let original = std::fs::read_to_string(&path).unwrap();
+
+    std::fs::write(&path, original.replacen("\"nonce\":1", "\"nonce\":9", 1)).unwrap();
+    assert!(audit::verify(&path).unwrap_err().to_string().contains("line 2: record hash mismatch"));
+
+    let without_second: Vec<&str> = original.lines().enumerate().filter(|(i, _)| *i != 1).map(|(_, l)| l).collect();
+    std::fs::write(&path, without_second.join("\n") + "\n").unwrap();
+    assert!(audit::verify(&path).unwrap_err().to_string().contains("line 2: expected seq 1"));
+}
+
+#[test]
+fn cli_reports_status() {
+    let path = temp_log("cli");
+    write_log(&path, 2);
+    let out = Command::new(env!("CARGO_BIN_EXE_audit_log")).arg("verify").arg(&path).output().unwrap();
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).starts_with("ok: 2 records, head 0x"));
+
+    let tampered = std::fs::read_to_string(&path).unwrap().replacen("\"action\":\"order\"", "\"action\":\"cancel\"", 1);
+    std::fs::write(&path, tampered).unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_audit_log")).arg("verify").arg(&path).output().unwrap();
+    assert_eq!(out.status.code(), Some(1));
+}
@@ -0,0 +1,89 @@
+// Connection ids of the L1 actions added to `Actions`. Each action is given the way an Elixir map
+// reaches the signer (keys sorted, as Jason encodes them) and must hash exactly like the same
+// action written in the reference SDK's wire order.
+
+use alloy::primitives::{keccak256, B256};
+use serde_json::{json, Value as JsonValue};
+use signer_nif::Actions;
+
+const NONCE: u64 = 1_234_567_890;
+const ADDR: &str = "0x1234567890123456789012345678901234567890";
+
+// keccak256(msgpack(action) || nonce || 0x00), with no vault and no expiresAfter.
+fn connection_id(action: &[u8]) -> B256 {
+    let mut preimage = action.to_vec();
+    preimage.extend(NONCE.to_be_bytes());
+    preimage.push(0);
+    keccak256(preimage)
+}
+
+fn sorted(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<_> = map.iter().map(|(k, v)| (k.clone(), sorted(v))).collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            JsonValue::Object(entries.into_iter().collect())
+        }
+        JsonValue::Array(items) => JsonValue::Array(items.iter().map(sorted).collect()),
+        other => other.clone(),
+    }
+}
+
+// Actions in wire order.
+fn cases() -> Vec<JsonValue> {
+    vec![
+        json!({ "type": "twapOrder", "twap": { "a": 1, "b": true, "s": "0.5", "r": false, "m": 30, "t": false } }),
+        json!({ "type": "twapCancel", "a": 1, "t": 7 }),
+        json!({ "type": "createVault", "name": "vault", "description": "a test vault", "initialUsd": 100_000_000, "nonce": NONCE }),
+        json!({ "type": "vaultModify", "vaultAddress": ADDR, "config": { "name": "vault", "description": "d", "portfolioManagerCutBps": 10, "allowDeposits": false } }),
+        json!({ "type": "vaultDistribute", "vaultAddress": ADDR }),
+        json!({ "type": "vaultTransfer", "vaultAddress": ADDR, "isDeposit": true, "usd": 5_000_000 }),
+        json!({ "type": "createSubAccount", "name": "sub" }),
+        json!({ "type": "subAccountModify", "subAccountUser": ADDR, "name": "sub" }),
+        json!({ "type": "subAccountTransfer", "subAccountUser": ADDR, "isDeposit": false, "usd": 1_000_000 }),
+        json!({ "type": "subAccountSpotTransfer", "subAccountUser": ADDR, "isDeposit": true, "token": "PURR:0xc4bf3f870c0e9465323c0b6ed28096c2", "amount": "1.5" }),
+        json!({ "type": "convertToMultiSigUser", "authorizedUsers": [ADDR], "threshold": 1 }),
+        json!({ "type": "cSignerAction", "unjailSelf": true }),
+        json!({ "type": "cValidatorAction", "action": { "type": "delegate", "validator": ADDR, "wei": 100 } }),
+        json!({ "type": "cValidatorAction", "action": { "type": "changeSigner", "newSigner": ADDR } }),
+        json!({ "type": "cValidatorAction", "action": { "type": "editValidator", "name": "v", "description": "d", "commissionBps": 5 } }),
+        json!({ "type": "agentEnableDexAbstraction", "enabled": true }),
+        json!({ "type": "agentSetAbstraction", "abstraction": "u" }),
+        json!({ "type": "linkStakingUser", "linkTo": ADDR }),
+        json!({ "type": "registerReferrer", "code": "CODE" }),
+        json!({ "type": "reserveRequestWeight", "weight": 10 }),
+        json!({ "type": "validatorL1Stream", "riskFreeRate": "0.05" }),
+        json!({ "type": "setDisplayName", "displayName": "name" }),
+        json!({ "type": "evmUserModify", "usingBigBlocks": true }),
+        json!({ "type": "scheduleCancel", "time": NONCE }),
+    ]
+}
+
+#[test]
+fn typed_actions_hash_in_wire_order_whatever_the_input_order() {
+    for wire in cases() {
+        let expected = connection_id(&rmp_serde::to_vec_named(&wire).unwrap());
+        let action: Actions = serde_json::from_value(sorted(&wire)).unwrap_or_else(|e| panic!("{wire}: {e}"));
+        let actual = connection_id(&rmp_serde::to_vec_named(&action).unwrap());
+        assert_eq!(actual, expected, "connection id mismatch for {wire}");
+        assert_eq!(serde_json::to_value(&action).unwrap().to_string(), wire.to_string());
+    }
+}
+
+fn twap_size(raw: &str) -> Result<String, serde_json::Error> {
+    let json = format!(r#"{{"type":"twapOrder","twap":{{"a":1,"b":true,"s":{raw},"r":false,"m":30,"t":false}}}}"#);
+    let action: Actions = serde_json::from_str(&json)?;
+    Ok(serde_json::to_value(&action).unwrap()["twap"]["s"].as_str().unwrap().to_string())
+}
+
+#[test]
+fn integer_sizes_stay_exact_past_i64() {
+    assert_eq!(twap_size("9223372036854775807").unwrap(), "9223372036854775807");
+    assert_eq!(twap_size("9223372036854775808").unwrap(), "9223372036854775808");
+    assert_eq!(twap_size("18446744073709551615").unwrap(), "18446744073709551615");
+
+    let err = twap_size("18446744073709551616").unwrap_err().to_string();
+    assert!(err.contains("too large to be represented exactly"), "{err}");
+    assert!(twap_size("1e300").is_err());
+    assert_eq!(twap_size("1.5").unwrap(), "1.5");
+}
@@ -0,0 +1,138 @@
+use std::process::Command;
+use std::str::FromStr;
+
+use alloy::primitives::{keccak256, Address, B256};
+use alloy::signers::local::PrivateKeySigner;
+use signer_nif::multi_sig_bundle::Bundle;
+
+const KEY: &str = "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff";
+const OTHER_KEY: &str = "0x0123456789012345678901234567890123456789012345678901234567890123";
+const USER: &str = "0x1234567890123456789012345678901234567890";
+
+fn signer(key: &str) -> PrivateKeySigner {
+    PrivateKeySigner::from_str(key).unwrap()
+}
+
+fn user() -> Address {
+    Address::from_str(USER).unwrap()
+}
+
+fn l1_bundle(threshold: usize) -> Bundle {
+    let signers = vec![signer(KEY).address(), signer(OTHER_KEY).address()];
+    let action = serde_json::json!({ "type": "scheduleCancel", "time": 1_234_567_890u64 });
+    Bundle::new(true, user(), signer(KEY).address(), action, 1_700_000_000_000, None, None, threshold, signers).unwrap()
+}
+
+#[test]
+fn collects_signatures_and_finalizes_once_the_threshold_is_met() {
+    let mut bundle = l1_bundle(2);
+    assert_eq!(bundle.sign(&signer(OTHER_KEY)).unwrap(), signer(OTHER_KEY).address());
+    assert!(!bundle.verify().ready);
+    assert!(bundle.finalize(&signer(KEY)).unwrap_err().to_string().contains("1 of 2 required signatures"));
+
+    bundle.sign(&signer(KEY)).unwrap();
+    let verification = bundle.verify();
+    assert!(verification.ready);
+    assert_eq!(verification.valid, 2);
+
+    let finalized = bundle.finalize(&signer(KEY)).unwrap();
+    assert_eq!(finalized.action["type"], "multiSig");
+    assert_eq!(finalized.action["payload"]["outerSigner"], format!("{:#x}", signer(KEY).address()));
+    assert_eq!(finalized.action["signatures"].as_array().unwrap().len(), 2);
+    assert_eq!(finalized.signature.recover_address_from_prehash(&finalized.digest).unwrap(), signer(KEY).address());
+
+    // multiSigActionHash is the connection id of the action without `type`, rebuilt here by hand.
+    let mut untagged = finalized.action.clone();
+    untagged.as_object_mut().unwrap().shift_remove("type");
+    let mut preimage = rmp_serde::to_vec_named(&untagged).unwrap();
+    preimage.extend(1_700_000_000_000u64.to_be_bytes());
+    preimage.push(0);
+    let payload = signer_nif::SendMultiSig {
+        signature_chain_id: 0xa4b1,
+        hyperliquid_chain: "Mainnet".into(),
+        multi_sig_action_hash: keccak256(preimage),
+        nonce: 1_700_000_000_000,
+    };
+    assert_eq!(signer_nif::Eip712::eip712_signing_hash(&payload), finalized.digest);
+}
+
+#[test]
+fn compact_form_round_trips() {
+    let mut bundle = l1_bundle(1);
+    bundle.sign(&signer(KEY)).unwrap();
+
+    let compact = bundle.to_compact().unwrap();
+    assert!(!compact.contains('='));
+    assert_eq!(Bundle::parse(&compact).unwrap(), bundle);
+    assert_eq!(Bundle::parse(&bundle.to_json().unwrap()).unwrap(), bundle);
+    assert_eq!(bundle.encode_like(&compact).unwrap(), compact);
+}
+
+#[test]
+fn rejects_unauthorized_duplicate_and_tampered_signatures() {
+    let mut bundle = l1_bundle(1);
+    let stranger = PrivateKeySigner::random();
+    assert!(bundle.sign(&stranger).unwrap_err().to_string().contains("is not an authorized signer"));
+
+    bundle.sign(&signer(KEY)).unwrap();
+    assert!(bundle.sign(&signer(KEY)).unwrap_err().to_string().contains("has already signed"));
+    assert!(bundle.finalize(&signer(OTHER_KEY)).unwrap_err().to_string().contains("outer signer"));
+
+    let mut tampered = bundle.clone();
+    tampered.nonce += 1;
+    let verification = tampered.verify();
+    assert!(!verification.ready);
+    assert!(verification.checks[0].error.as_deref().unwrap().contains("recovers to"));
+    assert!(tampered.finalize(&signer(KEY)).unwrap_err().to_string().contains("invalid signature"));
+}
+
+#[test]
+fn user_signed_actions_use_the_multi_sig_struct() {
+    let outer = Address::from_str("0xE5cA49Fb3bD9A581F0D1EF9CB5D7177Da08bf901").unwrap();
+    let action = serde_json::json!({ "type": "usdSend", "destination": USER, "amount": "1", "time": 1_234_567_890u64 });
+
+    let wrong_nonce = Bundle::new(false, user(), outer, action.clone(), 1, None, None, 1, vec![outer]);
+    assert!(wrong_nonce.unwrap_err().to_string().contains("own time or nonce"));
+
+    let bundle = Bundle::new(false, user(), outer, action, 1_234_567_890, None, None, 1, vec![outer]).unwrap();
+    assert_eq!(bundle.action["hyperliquidChain"], "Testnet");
+    assert_eq!(bundle.action["signatureChainId"], "0x66eee");
+    assert_eq!(
+        bundle.digest().unwrap(),
+        B256::from_str("0xfd1d68ac6682d659151b5d1c84487e4142526ed81f3a721467723fa74b767437").unwrap()
+    );
+}
+
+#[test]
+fn cli_creates_signs_verifies_and_finalizes() {
+    let bin = env!("CARGO_BIN_EXE_multi_sig_bundle");
+    let path = std::env::temp_dir().join(format!("hl-bundle-{}.txt", std::process::id()));
+    let signers = format!("{},{}", signer(KEY).address(), signer(OTHER_KEY).address());
+
+    let created = Command::new(bin)
+        .args(["create", "--user", USER, "--outer", &signer(KEY).address().to_string(), "--signers", &signers])
+        .args(["--threshold", "2", "--nonce", "7", "--action", r#"{"type":"scheduleCancel"}"#, "--compact"])
+        .output()
+        .unwrap();
+    assert!(created.status.success(), "{}", String::from_utf8_lossy(&created.stderr));
+    std::fs::write(&path, &created.stdout).unwrap();
+
+    for key in [KEY, OTHER_KEY] {
+        let signed = Command::new(bin).args(["sign", path.to_str().unwrap()]).env("HL_SIGNER_KEY", key).output().unwrap();
+        assert!(signed.status.success(), "{}", String::from_utf8_lossy(&signed.stderr));
+        assert!(!signed.stdout.starts_with(b"{"));
+        std::fs::write(&path, &signed.stdout).unwrap();
+    }
+
+    let verified = Command::new(bin).args(["verify", path.to_str().unwrap()]).output().unwrap();
+    assert!(verified.status.success());
+    assert!(String::from_utf8_lossy(&verified.stdout).contains("2 of 2 required signatures"));
+
+    let finalized = Command::new(bin).args(["finalize", path.to_str().unwrap()]).env("HL_SIGNER_KEY", KEY).output().unwrap();
+    assert!(finalized.status.success(), "{}", String::from_utf8_lossy(&finalized.stderr));
+    let body: serde_json::Value = serde_json::from_slice(&finalized.stdout).unwrap();
+    assert_eq!(body["action"]["type"], "multiSig");
+    assert_eq!(body["nonce"], 7);
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,104 @@
+#![cfg(unix)]
+
+// Round trip through the reference daemon: SocketSigner -> Unix socket -> signer_daemon.
+
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+use alloy::primitives::{keccak256, Address, B256};
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use signer_nif::backend::{SignerBackend, SocketSigner, DEFAULT_SOCKET_TIMEOUT, MAX_SOCKET_REPLY};
+
+const KEY: &str = "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff";
+
+struct Daemon { child: Child, socket: PathBuf }
+
+impl Daemon {
+    fn start(name: &str) -> Daemon {
+        let socket = std::env::temp_dir().join(format!("hl-signer-{}-{name}.sock", std::process::id()));
+        let child = Command::new(env!("CARGO_BIN_EXE_signer_daemon"))
+            .args(["--socket", socket.to_str().unwrap()])
+            .env("HL_SIGNER_KEY", KEY)
+            .spawn()
+            .expect("spawn signer_daemon");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while UnixStream::connect(&socket).is_err() {
+            assert!(Instant::now() < deadline, "signer_daemon did not start listening");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        Daemon { child, socket }
+    }
+
+    fn path(&self) -> &Path { &self.socket }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+#[test]
+fn signs_through_daemon_identically_to_local_key() {
+    let daemon = Daemon::start("roundtrip");
+    let local: PrivateKeySigner = KEY.parse().unwrap();
+    let remote = SocketSigner::connect(daemon.path(), Some(local.address()), DEFAULT_SOCKET_TIMEOUT).unwrap();
+
+    assert_eq!(SignerBackend::address(&remote), local.address());
+
+    let hash: B256 = keccak256(b"hyperliquid");
+    let sig = remote.sign_hash(&hash).unwrap();
+    assert_eq!(sig, local.sign_hash_sync(&hash).unwrap());
+    assert_eq!(sig.recover_address_from_prehash(&hash).unwrap(), local.address());
+}
+
+#[test]
+fn rejects_daemon_with_unexpected_address() {
+    let daemon = Daemon::start("mismatch");
+    let err = SocketSigner::connect(daemon.path(), Some(Address::ZERO), DEFAULT_SOCKET_TIMEOUT)
+        .err()
+        .expect("address mismatch must fail");
+    assert!(err.to_string().contains("does not match expected"), "{err}");
+}
+
+#[test]
+fn reports_missing_socket() {
+    let err = SocketSigner::connect("/nonexistent/hl-signer.sock", None, DEFAULT_SOCKET_TIMEOUT)
+        .err()
+        .expect("connect must fail");
+    assert!(err.to_string().starts_with("signer backend error: connect"), "{err}");
+}
+
+#[test]
+fn daemon_socket_is_private_to_its_user() {
+    let daemon = Daemon::start("mode");
+    // A completed round trip means the daemon is past bind and serving.
+    SocketSigner::connect(daemon.path(), None, DEFAULT_SOCKET_TIMEOUT).unwrap();
+    let mode = std::fs::metadata(daemon.path()).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn rejects_oversized_reply() {
+    let socket = std::env::temp_dir().join(format!("hl-signer-{}-flood.sock", std::process::id()));
+    let _ = std::fs::remove_file(&socket);
+    let listener = UnixListener::bind(&socket).unwrap();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let chunk = [b'a'; 4096];
+        for _ in 0..2 * MAX_SOCKET_REPLY / 4096 {
+            if stream.write_all(&chunk).is_err() {
+                break;
+            }
+        }
+    });
+    let err = SocketSigner::connect(&socket, None, DEFAULT_SOCKET_TIMEOUT).err().expect("oversized reply must fail");
+    let _ = std::fs::remove_file(&socket);
+    assert!(err.to_string().contains("exceeds"), "{err}");
+}
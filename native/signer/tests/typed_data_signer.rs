@@ -0,0 +1,105 @@
+// eth_signTypedData_v4 client against a local stand-in server that signs the received document
+// with an independent EIP-712 implementation (ethers-core) and the test key.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::str::FromStr;
+
+use alloy::primitives::{keccak256, Address, B256};
+use alloy::signers::{local::PrivateKeySigner, SignerSync};
+use ethers_core::types::transaction::eip712::{Eip712 as _, TypedData};
+use serde_json::{json, Value as JsonValue};
+use signer_nif::backend::SignerBackend;
+use signer_nif::l1_agent::L1Agent;
+use signer_nif::remote::{TypedDataSigner, DEFAULT_HTTP_TIMEOUT};
+use signer_nif::{ApproveAgent, ApproveBuilderFee, Eip712, SendMultiSig, SpotSend, UsdSend, Withdraw3};
+
+const KEY: &str = "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff";
+
+// Serve `requests` JSON-RPC calls; `sign_as` is the key the stand-in actually signs with.
+fn stand_in(requests: usize, sign_as: PrivateKeySigner, stringified: bool) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = v.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let request: JsonValue = serde_json::from_slice(&body).unwrap();
+            assert_eq!(request["method"], "eth_signTypedData_v4");
+
+            let document = if stringified {
+                serde_json::from_str(request["params"][1].as_str().unwrap()).unwrap()
+            } else {
+                request["params"][1].clone()
+            };
+            assert!(document["types"]["EIP712Domain"].is_array());
+            let typed: TypedData = serde_json::from_value(document).unwrap();
+            let digest = B256::from(typed.encode_eip712().unwrap());
+            let sig = sign_as.sign_hash_sync(&digest).unwrap();
+
+            let reply = json!({ "jsonrpc": "2.0", "id": request["id"], "result": sig.to_string() }).to_string();
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}", reply.len(), reply).unwrap();
+        }
+    });
+    url
+}
+
+fn payloads() -> Vec<Box<dyn Eip712>> {
+    let dest = "0x1234567890123456789012345678901234567890".to_string();
+    let addr = Address::from_str(&dest).unwrap();
+    vec![
+        Box::new(UsdSend { signature_chain_id: 42161, hyperliquid_chain: "Mainnet".into(), destination: dest.clone(), amount: "1000".into(), time: 1_234_567_890 }),
+        Box::new(Withdraw3 { signature_chain_id: 42161, hyperliquid_chain: "Testnet".into(), destination: dest.clone(), amount: "12.5".into(), time: 1 }),
+        Box::new(SpotSend { signature_chain_id: 421614, hyperliquid_chain: "Mainnet".into(), destination: dest, token: "PURR:0xc4bf3f870c0e9465323c0b6ed28096c2".into(), amount: "3".into(), time: 2 }),
+        Box::new(ApproveBuilderFee { signature_chain_id: 42161, hyperliquid_chain: "Mainnet".into(), builder: addr, max_fee_rate: "0.001%".into(), nonce: 3 }),
+        Box::new(ApproveAgent { signature_chain_id: 42161, hyperliquid_chain: "Mainnet".into(), agent_address: addr, agent_name: Some("bot".into()), nonce: 4 }),
+        Box::new(ApproveAgent { signature_chain_id: 42161, hyperliquid_chain: "Mainnet".into(), agent_address: addr, agent_name: None, nonce: 5 }),
+        Box::new(SendMultiSig { signature_chain_id: 0x66eee, hyperliquid_chain: "Testnet".into(), multi_sig_action_hash: keccak256(b"ms"), nonce: 6 }),
+        Box::new(L1Agent { source: "a".into(), connection_id: keccak256(b"cid"), chain_id: 1337 }),
+    ]
+}
+
+#[test]
+fn every_typed_payload_round_trips_through_remote_signer() {
+    let local: PrivateKeySigner = KEY.parse().unwrap();
+    for stringified in [false, true] {
+        let payloads = payloads();
+        let url = stand_in(payloads.len(), local.clone(), stringified);
+        let remote = TypedDataSigner::new(url, local.address(), DEFAULT_HTTP_TIMEOUT, stringified);
+        for payload in payloads {
+            let digest = payload.eip712_signing_hash();
+            let sig = remote.sign_typed(&digest, &|| payload.typed_data()).unwrap();
+            assert_eq!(sig, local.sign_hash_sync(&digest).unwrap());
+        }
+    }
+}
+
+#[test]
+fn rejects_signature_from_a_different_key() {
+    let local: PrivateKeySigner = KEY.parse().unwrap();
+    let url = stand_in(1, PrivateKeySigner::random(), false);
+    let remote = TypedDataSigner::new(url, local.address(), DEFAULT_HTTP_TIMEOUT, false);
+    let payload = UsdSend { signature_chain_id: 42161, hyperliquid_chain: "Mainnet".into(), destination: "0x0".into(), amount: "1".into(), time: 1 };
+
+    let err = remote.sign_typed(&payload.eip712_signing_hash(), &|| payload.typed_data()).unwrap_err();
+    assert!(err.to_string().contains("expected"), "{err}");
+}
+
+#[test]
+fn refuses_raw_digests() {
+    let remote = TypedDataSigner::new("http://127.0.0.1:9", Address::ZERO, DEFAULT_HTTP_TIMEOUT, false);
+    assert!(remote.sign_hash(&B256::ZERO).is_err());
+}
@@ -0,0 +1,165 @@
+// Digests of every `hyperliquid_tx!` struct against reference values, and the type strings
+// against the reference SDK's. The typed-data document of each payload is also hashed with an
+// independent EIP-712 implementation (ethers-core), so a member declared in the wrong order or
+// with the wrong type fails here rather than at the exchange.
+
+use std::str::FromStr;
+
+use alloy::primitives::{keccak256, Address, B256};
+use ethers_core::types::transaction::eip712::{Eip712 as _, TypedData};
+use signer_nif::{
+    ApproveAgent, ApproveBuilderFee, CDeposit, CWithdraw, Eip712, SendAsset, SendMultiSig, SendToEvmWithData, SpotSend, TokenDelegate,
+    UsdClassTransfer, UsdSend, UserSetAbstraction, Withdraw3,
+};
+
+const DEST: &str = "0x1234567890123456789012345678901234567890";
+
+fn dest() -> String {
+    DEST.to_string()
+}
+
+fn addr() -> Address {
+    Address::from_str(DEST).unwrap()
+}
+
+fn cases() -> Vec<(&'static str, Box<dyn Eip712>, &'static str)> {
+    vec![
+        (
+            "HyperliquidTransaction:UsdSend(string hyperliquidChain,string destination,string amount,uint64 time)",
+            Box::new(UsdSend { signature_chain_id: 0xa4b1, hyperliquid_chain: "Mainnet".into(), destination: dest(), amount: "1000".into(), time: 1_234_567_890 }),
+            "0x09ee49d47ae8718a5a5916f53e3c8acf1e5b45df4e4e0a880e4d1d08ff8c8a58",
+        ),
+        (
+            "HyperliquidTransaction:Withdraw(string hyperliquidChain,string destination,string amount,uint64 time)",
+            Box::new(Withdraw3 { signature_chain_id: 0x66eee, hyperliquid_chain: "Testnet".into(), destination: dest(), amount: "12.5".into(), time: 1 }),
+            "0x51760512615c37882faf090f69df533c1a5558e38b2b78839cba570a3611460d",
+        ),
+        (
+            "HyperliquidTransaction:SpotSend(string hyperliquidChain,string destination,string token,string amount,uint64 time)",
+            Box::new(SpotSend {
+                signature_chain_id: 0xa4b1,
+                hyperliquid_chain: "Mainnet".into(),
+                destination: dest(),
+                token: "PURR:0xc4bf3f870c0e9465323c0b6ed28096c2".into(),
+                amount: "3".into(),
+                time: 2,
+            }),
+            "0xabbec210e4177ec5ec191d8063b59c294e890072da784b5090c732c11ea8976a",
+        ),
+        (
+            "HyperliquidTransaction:ApproveBuilderFee(string hyperliquidChain,string maxFeeRate,address builder,uint64 nonce)",
+            Box::new(ApproveBuilderFee { signature_chain_id: 0xa4b1, hyperliquid_chain: "Mainnet".into(), max_fee_rate: "0.001%".into(), builder: addr(), nonce: 3 }),
+            "0x9a09574541615edf88d2ff46b8b2696e868c599f98ce7b359edafcfb6bf114b6",
+        ),
+        (
+            "HyperliquidTransaction:ApproveAgent(string hyperliquidChain,address agentAddress,string agentName,uint64 nonce)",
+            Box::new(ApproveAgent { signature_chain_id: 0xa4b1, hyperliquid_chain: "Mainnet".into(), agent_address: addr(), agent_name: Some("bot".into()), nonce: 4 }),
+            "0x77c9ade086801120c70698e74d5d8e45afb325f013e547794771c3b14b75bcc4",
+        ),
+        (
+            "HyperliquidTransaction:ApproveAgent(string hyperliquidChain,address agentAddress,string agentName,uint64 nonce)",
+            Box::new(ApproveAgent { signature_chain_id: 0xa4b1, hyperliquid_chain: "Mainnet".into(), agent_address: addr(), agent_name: None, nonce: 5 }),
+            "0xf3d92026062e831a1a183ad62b7e6a6de1290062910f67825ceaa0f008550e3c",
+        ),
+        (
+            "HyperliquidTransaction:UsdClassTransfer(string hyperliquidChain,string amount,bool toPerp,uint64 nonce)",
+            Box::new(UsdClassTransfer { signature_chain_id: 0xa4b1, hyperliquid_chain: "Mainnet".into(), amount: "5.5".into(), to_perp: true, nonce: 6 }),
+            "0x7d1649b84274d917e6cb0e6234d18418570a13f1abbf9f856d759f71674d8e01",
+        ),
+        (
+            "HyperliquidTransaction:SendMultiSig(string hyperliquidChain,bytes32 multiSigActionHash,uint64 nonce)",
+            Box::new(SendMultiSig { signature_chain_id: 0x66eee, hyperliquid_chain: "Testnet".into(), multi_sig_action_hash: keccak256(b"ms"), nonce: 7 }),
+            "0x23f13cc79d779bc52f1161d9e57660e99841f7d58cac8fa1901bc1170d4193dd",
+        ),
+        (
+            "HyperliquidTransaction:SendAsset(string hyperliquidChain,string destination,string sourceDex,string destinationDex,string token,string amount,string fromSubAccount,uint64 nonce)",
+            Box::new(SendAsset {
+                signature_chain_id: 0xa4b1,
+                hyperliquid_chain: "Mainnet".into(),
+                destination: dest(),
+                source_dex: "".into(),
+                destination_dex: "spot".into(),
+                token: "USDC".into(),
+                amount: "1".into(),
+                from_sub_account: "".into(),
+                nonce: 8,
+            }),
+            "0xbb7d405c05852e9b78af5e7da70e8f2f7ef29b9a52e5ca60a09da50a1f7113c0",
+        ),
+        (
+            "HyperliquidTransaction:SendToEvmWithData(string hyperliquidChain,string token,string amount,string sourceDex,string destinationRecipient,string addressEncoding,uint64 destinationChainId,uint64 gasLimit,string data,uint64 nonce)",
+            Box::new(SendToEvmWithData {
+                signature_chain_id: 0xa4b1,
+                hyperliquid_chain: "Mainnet".into(),
+                token: "USDC".into(),
+                amount: "1".into(),
+                source_dex: "".into(),
+                destination_recipient: dest(),
+                address_encoding: "hex".into(),
+                destination_chain_id: 1,
+                gas_limit: 200_000,
+                data: "0x".into(),
+                nonce: 9,
+            }),
+            "0xc0a42d5ab9fcb39f36c770ac16ace32d95453d93f9ed1c1cfc3fcb50206edaf1",
+        ),
+        (
+            "HyperliquidTransaction:CDeposit(string hyperliquidChain,uint64 wei,uint64 nonce)",
+            Box::new(CDeposit { signature_chain_id: 0xa4b1, hyperliquid_chain: "Mainnet".into(), wei: 100_000_000, nonce: 10 }),
+            "0x713b0e872406e71efd04d29bafc08f44f104ef2ef5ee8a7b47748e8f8661cd51",
+        ),
+        (
+            "HyperliquidTransaction:CWithdraw(string hyperliquidChain,uint64 wei,uint64 nonce)",
+            Box::new(CWithdraw { signature_chain_id: 0xa4b1, hyperliquid_chain: "Mainnet".into(), wei: 100_000_000, nonce: 11 }),
+            "0x168382ae2e17608f935ebfbf5d3a4169ab2a6bff34b4751f478244d7aa191934",
+        ),
+        (
+            "HyperliquidTransaction:TokenDelegate(string hyperliquidChain,address validator,uint64 wei,bool isUndelegate,uint64 nonce)",
+            Box::new(TokenDelegate { signature_chain_id: 0xa4b1, hyperliquid_chain: "Mainnet".into(), validator: addr(), wei: 1, is_undelegate: false, nonce: 12 }),
+            "0x419cd376701bedf5a9c1c9f4481e4a76aea58ab8aefdf2474ae5bb6ead898d48",
+        ),
+        (
+            "HyperliquidTransaction:UserSetAbstraction(string hyperliquidChain,string abstraction,uint64 nonce)",
+            Box::new(UserSetAbstraction { signature_chain_id: 0x66eee, hyperliquid_chain: "Testnet".into(), abstraction: "unifiedAccount".into(), nonce: 13 }),
+            "0x294f5d35ac3fdbfebceea3910e56e96822730f5195b4db061a12b596367fcc56",
+        ),
+    ]
+}
+
+// Rebuild "Primary(type name,...)" from a typed-data document.
+fn document_type_string(document: &serde_json::Value) -> String {
+    let primary = document["primaryType"].as_str().unwrap();
+    let members: Vec<String> = document["types"][primary]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| format!("{} {}", m["type"].as_str().unwrap(), m["name"].as_str().unwrap()))
+        .collect();
+    format!("{primary}({})", members.join(","))
+}
+
+#[test]
+fn digests_match_reference_values() {
+    for (type_string, payload, digest) in cases() {
+        assert_eq!(format!("{:#x}", payload.eip712_signing_hash()), digest, "{type_string}");
+    }
+}
+
+#[test]
+fn type_strings_match_reference_sdk() {
+    for (type_string, payload, _) in cases() {
+        assert_eq!(document_type_string(&payload.typed_data()), type_string);
+    }
+    assert_eq!(
+        ApproveBuilderFee::type_string(),
+        "HyperliquidTransaction:ApproveBuilderFee(string hyperliquidChain,string maxFeeRate,address builder,uint64 nonce)"
+    );
+}
+
+#[test]
+fn typed_data_documents_hash_to_the_same_digest() {
+    for (type_string, payload, _) in cases() {
+        let typed: TypedData = serde_json::from_value(payload.typed_data()).unwrap();
+        assert_eq!(B256::from(typed.encode_eip712().unwrap()), payload.eip712_signing_hash(), "{type_string}");
+    }
+}
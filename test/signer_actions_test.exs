@@ -0,0 +1,126 @@
+defmodule Hyperliquid.SignerActionsTest do
+  use ExUnit.Case, async: true
+
+  alias Hyperliquid.Signer
+  alias Jason.OrderedObject
+
+  @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+  @nonce 1_234_567_890
+  @address "0x1234567890123456789012345678901234567890"
+
+  # Actions in wire order, so the untyped JSON hash is the expected connection id.
+  @actions [
+    OrderedObject.new(type: "noop"),
+    OrderedObject.new(
+      type: "twapOrder",
+      twap: OrderedObject.new(a: 1, b: true, s: "0.5", r: false, m: 30, t: false)
+    ),
+    OrderedObject.new(type: "twapCancel", a: 1, t: 7),
+    OrderedObject.new(type: "createVault", name: "vault", description: "a test vault", initialUsd: 100_000_000, nonce: @nonce),
+    OrderedObject.new(type: "vaultModify", vaultAddress: @address, config: OrderedObject.new(name: "vault", allowDeposits: false)),
+    OrderedObject.new(type: "vaultDistribute", vaultAddress: @address),
+    OrderedObject.new(type: "vaultTransfer", vaultAddress: @address, isDeposit: true, usd: 5_000_000),
+    OrderedObject.new(type: "createSubAccount", name: "sub"),
+    OrderedObject.new(type: "subAccountModify", subAccountUser: @address, name: "sub"),
+    OrderedObject.new(type: "convertToMultiSigUser", authorizedUsers: [@address], threshold: 1),
+    OrderedObject.new(type: "cSignerAction", unjailSelf: true),
+    OrderedObject.new(type: "cValidatorAction", action: OrderedObject.new(type: "delegate", validator: @address, wei: 100)),
+    OrderedObject.new(type: "agentEnableDexAbstraction", enabled: true),
+    OrderedObject.new(type: "agentSetAbstraction", abstraction: "u"),
+    OrderedObject.new(type: "linkStakingUser", linkTo: @address),
+    OrderedObject.new(type: "registerReferrer", code: "CODE"),
+    OrderedObject.new(type: "reserveRequestWeight", weight: 10),
+    OrderedObject.new(type: "validatorL1Stream", riskFreeRate: "0.05"),
+    OrderedObject.new(type: "setDisplayName", displayName: "name")
+  ]
+
+  test "every L1 action is signed over its canonical form" do
+    for action <- @actions do
+      json = Jason.encode!(action)
+      expected = Signer.compute_connection_id_ex(json, @nonce, nil, nil)
+
+      assert %{"connection_id" => ^expected} = Signer.sign_exchange_action_ex(@priv_key, json, @nonce, true, nil, nil),
+             "connection id mismatch for #{json}"
+    end
+  end
+
+  test "field order in the input does not change the connection id" do
+    ordered = Jason.encode!(OrderedObject.new(type: "twapCancel", a: 1, t: 7))
+    sorted = Jason.encode!(%{type: "twapCancel", a: 1, t: 7})
+
+    assert Signer.sign_exchange_action_ex(@priv_key, sorted, @nonce, true, nil, nil)["connection_id"] ==
+             Signer.compute_connection_id_ex(ordered, @nonce, nil, nil)
+  end
+
+  test "rejects unknown fields" do
+    assert {:error, message} = Signer.sign_exchange_action_ex(@priv_key, ~s({"type":"twapCancel","a":1,"t":7,"x":1}), @nonce, true, nil, nil)
+    assert message =~ "unknown field `x`"
+  end
+
+  test "rejects user-signed actions" do
+    action =
+      ~s({"type":"usdSend","signatureChainId":"0xa4b1","hyperliquidChain":"Mainnet","destination":"#{@address}","amount":"1","time":1})
+
+    assert {:error, "usdSend is a user-signed action and cannot be signed as an L1 action"} =
+             Signer.sign_exchange_action_ex(@priv_key, action, @nonce, true, nil, nil)
+  end
+
+  describe "encode_action" do
+    test "emits wire order whatever the input order" do
+      ordered = Jason.encode!(OrderedObject.new(type: "twapCancel", a: 1, t: 7))
+
+      for action <- [%{type: "twapCancel", t: 7, a: 1}, [t: 7, type: "twapCancel", a: 1], %{"a" => 1, "t" => 7, "type" => "twapCancel"}] do
+        assert %{"type" => "twapCancel", "json" => ^ordered, "msgpack" => msgpack} = Signer.encode_action(action)
+        assert is_binary(msgpack)
+      end
+    end
+
+    test "encodes nested actions and user-signed actions" do
+      action = %{type: "twapOrder", twap: %{a: 1, b: true, s: "0.5", r: false, m: 30, t: false}}
+
+      assert Signer.encode_action(action)["json"] ==
+               ~s({"type":"twapOrder","twap":{"a":1,"b":true,"s":"0.5","r":false,"m":30,"t":false}})
+
+      send_asset = %{
+        type: "sendAsset",
+        nonce: 1,
+        fromSubAccount: "",
+        amount: "1",
+        token: "USDC",
+        destinationDex: "spot",
+        sourceDex: "",
+        destination: @address,
+        hyperliquidChain: "Mainnet",
+        signatureChainId: "0xa4b1"
+      }
+
+      assert Signer.encode_action(send_asset)["json"] ==
+               ~s({"type":"sendAsset","signatureChainId":"0xa4b1","hyperliquidChain":"Mainnet","destination":"#{@address}","sourceDex":"","destinationDex":"spot","token":"USDC","amount":"1","fromSubAccount":"","nonce":1})
+    end
+
+    test "rejects malformed actions" do
+      assert {:error, message} = Signer.encode_action(%{type: "twapCancel", a: 1})
+      assert message =~ "missing field `t`"
+      assert {:error, _} = Signer.encode_action(%{type: "twapCancel", a: 1, t: {7}})
+    end
+  end
+
+  describe "sign_action" do
+    test "signs the canonical encoding" do
+      %{"json" => json} = Signer.encode_action(%{type: "setDisplayName", displayName: "name"})
+
+      assert %{"connection_id" => cid, "action_json" => ^json} =
+               Signer.sign_action(@priv_key, %{displayName: "name", type: "setDisplayName"}, @nonce, true, nil, nil)
+
+      assert cid == Signer.compute_connection_id_ex(json, @nonce, nil, nil)
+      assert Map.delete(Signer.sign_action(@priv_key, [type: "setDisplayName", displayName: "name"], @nonce, true, nil, nil), "action_json") ==
+               Signer.sign_exchange_action_ex(@priv_key, json, @nonce, true, nil, nil)
+    end
+
+    test "rejects user-signed actions" do
+      action = %{type: "cDeposit", signatureChainId: "0xa4b1", hyperliquidChain: "Mainnet", wei: 1, nonce: 1}
+      assert {:error, "cDeposit is a user-signed action and cannot be signed as an L1 action"} =
+               Signer.sign_action(@priv_key, action, @nonce, true, nil, nil)
+    end
+  end
+end
@@ -0,0 +1,55 @@
+defmodule Hyperliquid.SignerAuditTest do
+  # The audit log is process-wide inside the NIF, so these tests must not run
+  # alongside other signing tests.
+  use ExUnit.Case, async: false
+
+  alias Hyperliquid.Signer
+
+  @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+  @address "0xE5cA49Fb3bD9A581F0D1EF9CB5D7177Da08bf901"
+
+  setup do
+    path = Path.join(System.tmp_dir!(), "hl-audit-#{System.unique_integer([:positive])}.jsonl")
+    :ok = Signer.open_audit_log(path)
+
+    on_exit(fn ->
+      Signer.close_audit_log()
+      File.rm(path)
+    end)
+
+    %{path: path}
+  end
+
+  defp records(path) do
+    path |> File.read!() |> String.split("\n", trim: true) |> Enum.map(&Jason.decode!/1)
+  end
+
+  test "records every signature in a verifiable chain", %{path: path} do
+    action = Jason.encode!(%{type: "cancel", cancels: [%{a: 0, o: 1}]})
+    l1 = Signer.sign_exchange_action_ex(@priv_key, action, 42, true, nil, nil)
+    user = Signer.sign_usd_send(@priv_key, "0x1234567890123456789012345678901234567890", "1", 7, true)
+
+    assert [first, second] = records(path)
+
+    assert %{"seq" => 0, "action" => "cancel", "nonce" => 42, "vault_address" => nil} = first
+    assert first["connection_id"] == l1["connection_id"]
+    assert first["signature"] == l1["signature"]
+    assert String.downcase(first["signer"]) == String.downcase(@address)
+
+    assert %{"seq" => 1, "action" => "usdSend", "nonce" => 7, "connection_id" => nil} = second
+    assert second["signature"] == user["signature"]
+    assert second["prev"] == first["hash"]
+
+    assert %{"records" => 2, "head" => head} = Signer.verify_audit_log(path)
+    assert head == second["hash"]
+  end
+
+  test "verification fails when a record is edited", %{path: path} do
+    Signer.sign_usd_send(@priv_key, "0x1234567890123456789012345678901234567890", "1", 7, true)
+    Signer.close_audit_log()
+
+    File.write!(path, String.replace(File.read!(path), ~s("nonce":7), ~s("nonce":8)))
+
+    assert {:error, "audit error: line 1: record hash mismatch"} = Signer.verify_audit_log(path)
+  end
+end
@@ -0,0 +1,69 @@
+defmodule Hyperliquid.SignerExplorerTest do
+  use ExUnit.Case, async: true
+
+  alias Hyperliquid.Signer
+
+  @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+  @address "0xE5cA49Fb3bD9A581F0D1EF9CB5D7177Da08bf901"
+  @destination "0x1234567890123456789012345678901234567890"
+
+  defp sig_json(%{"r" => r, "s" => s, "v" => v}), do: ~s({"r":"#{r}","s":"#{s}","v":#{v}})
+
+  test "verifies an L1 action" do
+    action = ~s({"type":"cancel","cancels":[{"a":0,"o":1}]})
+    cid = Signer.compute_connection_id_ex(action, 5, nil, nil)
+    sig = Signer.sign_l1_action(@priv_key, cid, true)
+
+    tx = ~s({"tx":{"time":1,"user":"#{@address}","action":#{action},"nonce":5,"signature":#{sig_json(sig)},"vaultAddress":null}})
+
+    assert %{
+             "kind" => "l1",
+             "action_type" => "cancel",
+             "signer" => @address,
+             "user" => @address,
+             "matches_user" => true,
+             "connection_id" => ^cid
+           } = Signer.verify_explorer_tx(tx, true)
+
+    assert %{"matches_user" => false} = Signer.verify_explorer_tx(tx, false)
+  end
+
+  test "verifies a user-signed action with a hex signature" do
+    sig = Signer.sign_usd_send(@priv_key, @destination, "10", 99, true)
+
+    action =
+      ~s({"type":"usdSend","signatureChainId":"0xa4b1","hyperliquidChain":"Mainnet","destination":"#{@destination}","amount":"10","time":99})
+
+    tx = ~s({"user":"#{@address}","action":#{action},"nonce":99,"signature":"#{sig["signature"]}"})
+
+    assert %{"kind" => "user_signed", "signer" => @address, "matches_user" => true, "connection_id" => nil} =
+             Signer.verify_explorer_tx(tx, true)
+
+    tampered = String.replace(tx, ~s("amount":"10"), ~s("amount":"11"))
+    assert %{"matches_user" => false} = Signer.verify_explorer_tx(tampered, true)
+  end
+
+  test "verifies a batch with per-item results in input order" do
+    txs =
+      for nonce <- 1..50 do
+        action = ~s({"type":"cancel","cancels":[{"a":0,"o":#{nonce}}]})
+        cid = Signer.compute_connection_id_ex(action, nonce, nil, nil)
+        sig = Signer.sign_l1_action(@priv_key, cid, true)
+        ~s({"user":"#{@address}","action":#{action},"nonce":#{nonce},"signature":#{sig_json(sig)}})
+      end
+
+    results = Signer.verify_explorer_txs(txs ++ ["not json"], true)
+
+    assert length(results) == 51
+    assert Enum.all?(Enum.take(results, 50), &match?({:ok, %{"matches_user" => true}}, &1))
+    assert {:error, "json parse error: " <> _} = List.last(results)
+
+    assert Enum.map(Enum.take(results, 50), fn {:ok, r} -> r end) ==
+             Enum.map(txs, &Signer.verify_explorer_tx(&1, true))
+  end
+
+  test "reports missing fields" do
+    assert {:error, "parse error: missing signature"} =
+             Signer.verify_explorer_tx(~s({"user":"#{@address}","action":{"type":"noop"},"nonce":1}), true)
+  end
+end
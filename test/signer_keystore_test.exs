@@ -0,0 +1,66 @@
+defmodule Hyperliquid.SignerKeystoreTest do
+  use ExUnit.Case, async: true
+
+  alias Hyperliquid.Signer
+  alias Hyperliquid.Api.Exchange.KeyUtils
+
+  @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+  @password "correct horse battery staple"
+
+  setup do
+    dir = Path.join(System.tmp_dir!(), "hl_keystore_#{System.unique_integer([:positive])}")
+    File.mkdir_p!(dir)
+    on_exit(fn -> File.rm_rf!(dir) end)
+    {:ok, dir: dir}
+  end
+
+  test "encrypts an existing key and decrypts it back to the same wallet", %{dir: dir} do
+    %{"address" => address, "path" => path} =
+      Signer.encrypt_keystore(@priv_key, dir, @password, "master.json")
+
+    assert path == Path.join(dir, "master.json")
+    assert address == Signer.derive_address(@priv_key)
+
+    assert %{"version" => 3, "crypto" => %{"kdf" => _}} = path |> File.read!() |> Jason.decode!()
+    refute File.read!(path) =~ String.trim_leading(@priv_key, "0x")
+
+    handle = Signer.load_keystore(path, @password)
+    assert Signer.derive_address(handle) == address
+
+    assert Signer.sign_usd_send(handle, address, "1", 1, true) ==
+             Signer.sign_usd_send(@priv_key, address, "1", 1, true)
+  end
+
+  test "new_keystore generates a key without returning it", %{dir: dir} do
+    info = Signer.new_keystore(dir, @password, nil)
+
+    assert Map.keys(info) |> Enum.sort() == ["address", "path"]
+    assert File.exists?(info["path"])
+    assert Signer.derive_address(Signer.load_keystore(info["path"], @password)) == info["address"]
+  end
+
+  test "wrong passphrase is rejected", %{dir: dir} do
+    %{"path" => path} = Signer.encrypt_keystore(@priv_key, dir, @password, nil)
+
+    assert {:error, "keystore error: " <> _} = Signer.load_keystore(path, "wrong")
+  end
+
+  test "KeyUtils reloads a keystore that was replaced on disk", %{dir: dir} do
+    %{"path" => path, "address" => first} = Signer.encrypt_keystore(@priv_key, dir, @password, "rotating.json")
+    assert Signer.derive_address(KeyUtils.load_keystore!(path, @password)) == first
+
+    File.rm!(path)
+    %{"address" => second} = Signer.new_keystore(dir, @password, "rotating.json")
+
+    assert second != first
+    handle = KeyUtils.load_keystore!(path, @password)
+    assert Signer.derive_address(handle) == second
+    assert KeyUtils.load_keystore!(path, @password) == handle
+  end
+
+  test "KeyUtils rejects a wrong password for a keystore it has not decrypted", %{dir: dir} do
+    %{"path" => path} = Signer.encrypt_keystore(@priv_key, dir, @password, "fresh.json")
+
+    assert_raise ArgumentError, fn -> KeyUtils.load_keystore!(path, "wrong") end
+  end
+end
@@ -2,6 +2,7 @@ defmodule Hyperliquid.SignerL1Test do
   use ExUnit.Case, async: true
 
   alias Hyperliquid.Signer
+  alias Hyperliquid.Api.Exchange.KeyUtils
 
   @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
 
@@ -164,4 +165,64 @@ defmodule Hyperliquid.SignerL1Test do
       assert sig["v"] == 27
     end
   end
+
+  describe "wallet handle" do
+    test "load_wallet returns a handle that signs identically to the hex key" do
+      handle = Signer.load_wallet(@priv_key)
+      assert is_reference(handle)
+
+      action_json = Jason.encode!(@action)
+
+      assert Signer.sign_exchange_action_ex(handle, action_json, @nonce, true, @vault, @expires) ==
+               Signer.sign_exchange_action_ex(@priv_key, action_json, @nonce, true, @vault, @expires)
+
+      assert Signer.derive_address(handle) == Signer.derive_address(@priv_key)
+    end
+
+    test "load_wallet rejects an invalid key" do
+      assert {:error, _} = Signer.load_wallet("0xnotakey")
+    end
+
+    test "KeyUtils caches a bounded number of wallet handles" do
+      handle = KeyUtils.load_wallet!(@priv_key)
+      assert KeyUtils.load_wallet!(@priv_key) == handle
+
+      for _ <- 1..100 do
+        KeyUtils.load_wallet!("0x" <> Base.encode16(:crypto.strong_rand_bytes(32), case: :lower))
+      end
+
+      assert :ets.info(:hyperliquid_wallets, :size) <= 64
+    end
+  end
+
+  describe "recover_l1_action_signer" do
+    test "recovers the signing address and connection id" do
+      action_json = Jason.encode!(@action)
+
+      for is_mainnet <- [true, false] do
+        cid = Signer.compute_connection_id_ex(action_json, @nonce, @vault, @expires)
+        %{"r" => r, "s" => s, "v" => v} = Signer.sign_l1_action(@priv_key, cid, is_mainnet)
+
+        assert Signer.recover_l1_action_signer(action_json, @nonce, @vault, @expires, is_mainnet, r, s, v) ==
+                 %{"address" => Signer.derive_address(@priv_key), "connection_id" => cid}
+      end
+    end
+
+    test "recovers a different address when any signed field differs" do
+      action_json = Jason.encode!(@action)
+      cid = Signer.compute_connection_id_ex(action_json, @nonce, nil, nil)
+      %{"r" => r, "s" => s, "v" => v} = Signer.sign_l1_action(@priv_key, cid, true)
+      address = Signer.derive_address(@priv_key)
+
+      assert %{"address" => ^address} = Signer.recover_l1_action_signer(action_json, @nonce, nil, nil, true, r, s, v)
+      refute match?(%{"address" => ^address}, Signer.recover_l1_action_signer(action_json, @nonce + 1, nil, nil, true, r, s, v))
+      refute match?(%{"address" => ^address}, Signer.recover_l1_action_signer(action_json, @nonce, @vault, nil, true, r, s, v))
+      refute match?(%{"address" => ^address}, Signer.recover_l1_action_signer(action_json, @nonce, nil, nil, false, r, s, v))
+    end
+
+    test "rejects a malformed signature" do
+      assert {:error, "parse error: invalid v: 29"} =
+               Signer.recover_l1_action_signer(Jason.encode!(@action), @nonce, nil, nil, true, "0x1", "0x1", 29)
+    end
+  end
 end
@@ -0,0 +1,35 @@
+defmodule Hyperliquid.SignerMnemonicTest do
+  use ExUnit.Case, async: true
+
+  alias Hyperliquid.Signer
+
+  # Well-known development mnemonic (Hardhat / Anvil default accounts)
+  @phrase "test test test test test test test test test test test junk"
+
+  test "derives the default BIP-44 Ethereum account" do
+    assert Signer.derive_mnemonic_address(@phrase, nil, nil) ==
+             "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+  end
+
+  test "derives accounts along an explicit path" do
+    assert Signer.derive_mnemonic_address(@phrase, nil, "m/44'/60'/0'/0/1") ==
+             "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+  end
+
+  test "passphrase changes the derived key" do
+    assert Signer.derive_mnemonic_address(@phrase, "TREZOR", nil) ==
+             "0x9313778B3753108128B9c476EBDd42FbD566F4Ed"
+  end
+
+  test "load_mnemonic returns a wallet handle for the same account" do
+    path = "m/44'/60'/0'/0/1"
+    handle = Signer.load_mnemonic(@phrase, nil, path)
+
+    assert Signer.derive_address(handle) == Signer.derive_mnemonic_address(@phrase, nil, path)
+  end
+
+  test "rejects invalid phrases and paths" do
+    assert {:error, "mnemonic error: " <> _} = Signer.derive_mnemonic_address("foo bar", nil, nil)
+    assert {:error, "mnemonic error: " <> _} = Signer.load_mnemonic(@phrase, nil, "m/x")
+  end
+end
@@ -198,4 +198,155 @@ defmodule Hyperliquid.SignerMultiSigTest do
       assert sig["v"] == 27
     end
   end
+
+  describe "multi-sig co-signer signatures" do
+    @multi_sig_user "0x1234567890123456789012345678901234567890"
+    @inner %{type: "scheduleCancel", time: 1_234_567_890}
+
+    test "co-signers sign the [multiSigUser, outerSigner, action] envelope" do
+      outer = Signer.derive_address(@priv_key)
+      sig = Signer.sign_multi_sig_l1_payload(@priv_key, @inner, @multi_sig_user, outer, @nonce, true, @vault, @expires)
+
+      envelope =
+        ~s(["#{@multi_sig_user}","#{String.downcase(outer)}",{"type":"scheduleCancel","time":1234567890}])
+
+      assert sig["connection_id"] == Signer.compute_connection_id_ex(envelope, @nonce, @vault, @expires)
+
+      recovered = Signer.recover_l1_action_signer(envelope, @nonce, @vault, @expires, true, sig["r"], sig["s"], sig["v"])
+      assert String.downcase(recovered["address"]) == String.downcase(outer)
+    end
+
+    test "assemble_multi_sig_action builds the multiSig action signed by the outer signer" do
+      outer = Signer.derive_address(@priv_key)
+      sig = Signer.sign_multi_sig_l1_payload(@priv_key, @inner, @multi_sig_user, outer, @nonce, false, nil, nil)
+
+      json = Signer.assemble_multi_sig_action(@multi_sig_user, outer, @inner, [sig], false)
+      action = Jason.decode!(json)
+
+      assert String.starts_with?(json, ~s({"type":"multiSig",))
+      assert action["signatureChainId"] == "0x66eee"
+      assert [%{"v" => v}] = action["signatures"]
+      assert v == sig["v"]
+      assert action["payload"]["outerSigner"] == String.downcase(outer)
+      assert action["payload"]["action"] == %{"type" => "scheduleCancel", "time" => 1_234_567_890}
+
+      outer_sig = Signer.sign_multi_sig_action_ex(@priv_key, json, @nonce, false, nil, nil)
+      assert Signer.verify_multi_sig_action(outer, json, @nonce, false, nil, nil, outer_sig["r"], outer_sig["s"], outer_sig["v"])
+    end
+
+    test "assemble_multi_sig_action rejects an empty signature list and bad addresses" do
+      assert {:error, _} = Signer.assemble_multi_sig_action(@multi_sig_user, @vault, @inner, [], true)
+      assert {:error, _} = Signer.assemble_multi_sig_action("0x12", @vault, @inner, [%{r: "0x1", s: "0x2", v: 27}], true)
+    end
+  end
+
+  describe "multi-sig user-signed actions" do
+    @usd_send %{
+      type: "usdSend",
+      destination: "0x1234567890123456789012345678901234567890",
+      amount: "1",
+      time: 1_234_567_890
+    }
+
+    test "co-signers sign with payloadMultiSigUser and outerSigner added" do
+      outer = Signer.derive_address(@priv_key)
+      sig = Signer.sign_multi_sig_user_signed_payload(@priv_key, @usd_send, @vault, outer, false)
+
+      assert hex32(sig["r"]) ==
+               "0x329e9b12365972d0e83d465528788ffa7b33e7250349b96b0b2517b0ad5ab457"
+
+      assert hex32(sig["s"]) ==
+               "0x233077da076a0ef8f28a241eb5e66703c9b966c7fddfce1611f3dbf385b30ce0"
+
+      assert sig["v"] == 27
+
+      assert sig["action_json"] ==
+               ~s({"type":"usdSend","signatureChainId":"0x66eee","hyperliquidChain":"Testnet","destination":"0x1234567890123456789012345678901234567890","amount":"1","time":1234567890})
+
+      plain = Signer.sign_user_signed_action(@priv_key, @usd_send, false)
+      refute plain["r"] == sig["r"]
+    end
+
+    test "finalize_multi_sig_action wraps the signatures and signs as the outer signer" do
+      outer = Signer.derive_address(@priv_key)
+      sig = Signer.sign_multi_sig_user_signed_payload(@priv_key, @usd_send, @vault, outer, false)
+
+      finalized = Signer.finalize_multi_sig_action(@priv_key, @vault, @usd_send, [sig], @usd_send.time, false, nil, nil)
+      action = Jason.decode!(finalized["action_json"])
+
+      assert action["payload"]["action"] == Jason.decode!(sig["action_json"])
+      assert action["payload"]["outerSigner"] == String.downcase(outer)
+
+      assert finalized["action_json"] ==
+               Signer.assemble_multi_sig_action(@vault, outer, @usd_send, [sig], false)
+
+      assert Signer.verify_multi_sig_action(
+               outer,
+               finalized["action_json"],
+               @usd_send.time,
+               false,
+               nil,
+               nil,
+               finalized["r"],
+               finalized["s"],
+               finalized["v"]
+             )
+    end
+  end
+
+  describe "pending multi-sig bundles" do
+    @other_key "0x0123456789012345678901234567890123456789012345678901234567890123"
+    @cancel %{type: "scheduleCancel", time: 1_234_567_890}
+
+    setup do
+      outer = Signer.derive_address(@priv_key)
+      other = Signer.derive_address(@other_key)
+      bundle = Signer.new_multi_sig_bundle(@vault, outer, @cancel, @nonce, 2, [outer, other], true, nil, nil)
+      %{outer: outer, other: other, bundle: bundle}
+    end
+
+    test "collects signatures in either form and finalizes at the threshold", %{outer: outer, other: other, bundle: bundle} do
+      compact = Signer.multi_sig_bundle_to_compact(bundle)
+      signed = Signer.sign_multi_sig_bundle(@priv_key, compact)
+      refute String.starts_with?(signed, "{")
+
+      assert %{"valid" => 1, "threshold" => 2, "ready" => false} = Signer.verify_multi_sig_bundle(signed)
+      assert {:error, _} = Signer.finalize_multi_sig_bundle(@priv_key, signed)
+
+      other_sig = Signer.sign_multi_sig_l1_payload(@other_key, @cancel, @vault, outer, @nonce, true, nil, nil)
+      signed = Signer.add_multi_sig_bundle_signature(signed, other_sig)
+
+      assert %{"ready" => true, "signatures" => [%{"valid" => true}, %{"valid" => true, "signer" => ^other}]} =
+               Signer.verify_multi_sig_bundle(signed)
+
+      own_sig = Signer.sign_multi_sig_l1_payload(@priv_key, @cancel, @vault, outer, @nonce, true, nil, nil)
+      finalized = Signer.finalize_multi_sig_bundle(@priv_key, signed)
+
+      assert finalized["nonce"] == @nonce
+
+      assert finalized["action_json"] ==
+               Signer.assemble_multi_sig_action(@vault, outer, @cancel, [own_sig, other_sig], true)
+
+      assert Signer.verify_multi_sig_action(
+               outer,
+               finalized["action_json"],
+               @nonce,
+               true,
+               nil,
+               nil,
+               finalized["r"],
+               finalized["s"],
+               finalized["v"]
+             )
+    end
+
+    test "rejects signatures from outside the authorized list", %{outer: outer, bundle: bundle} do
+      stranger = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+      assert {:error, message} = Signer.sign_multi_sig_bundle(stranger, bundle)
+      assert message =~ "not an authorized signer"
+
+      assert {:error, _} = Signer.new_multi_sig_bundle(@vault, outer, @cancel, @nonce, 3, [outer], true, nil, nil)
+      assert Signer.multi_sig_bundle_to_json(Signer.multi_sig_bundle_to_compact(bundle)) == bundle
+    end
+  end
 end
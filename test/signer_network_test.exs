@@ -0,0 +1,81 @@
+defmodule Hyperliquid.SignerNetworkTest do
+  # The network profile is process-wide inside the NIF, so these tests must not run
+  # alongside other signing tests.
+  use ExUnit.Case, async: false
+
+  alias Hyperliquid.{Signer, Utils}
+
+  @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+  @destination "0x1234567890123456789012345678901234567890"
+
+  setup do
+    on_exit(fn -> Signer.clear_network_profile() end)
+  end
+
+  test "built-in profiles" do
+    assert %{
+             "name" => "mainnet",
+             "hyperliquid_chain" => "Mainnet",
+             "signature_chain_id" => "0xa4b1",
+             "agent_chain_id" => 1337,
+             "agent_source" => "a"
+           } = Signer.network_profile(true)
+
+    assert %{"hyperliquid_chain" => "Testnet", "signature_chain_id" => "0x66eee", "agent_source" => "b"} =
+             Signer.network_profile(false)
+
+    assert Utils.signature_chain_id(false) == "0x66eee"
+    assert Utils.hyperliquid_chain(true) == "Mainnet"
+  end
+
+  test "user-signed payloads take signatureChainId from the profile" do
+    sig = Signer.sign_user_signed_action(@priv_key, %{type: "cDeposit", wei: 1, nonce: 1}, false)
+    assert Jason.decode!(sig["action_json"])["signatureChainId"] == "0x66eee"
+  end
+
+  test "a devnet profile overrides only the fields it names" do
+    before = Signer.sign_usd_send(@priv_key, @destination, "1", 1, false)
+
+    :ok = Signer.set_network_profile(%{name: "devnet", is_mainnet: false, signature_chain_id: "0x539"})
+
+    assert %{"name" => "devnet", "signature_chain_id" => "0x539", "hyperliquid_chain" => "Testnet"} =
+             Signer.network_profile(false)
+
+    assert Signer.network_profile(true)["signature_chain_id"] == "0xa4b1"
+
+    devnet = Signer.sign_usd_send(@priv_key, @destination, "1", 1, false)
+    assert devnet["signature"] != before["signature"]
+
+    action = %{
+      type: "usdSend",
+      signatureChainId: "0x539",
+      hyperliquidChain: "Testnet",
+      destination: @destination,
+      amount: "1",
+      time: 1
+    }
+
+    assert Map.take(Signer.sign_user_signed_action(@priv_key, action, false), ["r", "s", "v"]) ==
+             Map.take(devnet, ["r", "s", "v"])
+  end
+
+  test "the L1 agent source follows the profile" do
+    action_json = Jason.encode!(%{type: "noop"})
+    mainnet = Signer.sign_exchange_action(@priv_key, action_json, 1, true, nil)
+
+    :ok = Signer.set_network_profile(%{name: "local", is_mainnet: true, agent_source: "b"})
+
+    assert Signer.sign_exchange_action(@priv_key, action_json, 1, true, nil)["signature"] ==
+             Signer.sign_exchange_action(@priv_key, action_json, 1, false, nil)["signature"]
+
+    refute Signer.sign_exchange_action(@priv_key, action_json, 1, true, nil)["signature"] == mainnet["signature"]
+  end
+
+  test "bad profiles are rejected" do
+    assert {:error, message} = Signer.set_network_profile(:devnet)
+    assert message =~ "unknown network"
+    assert {:error, message} = Signer.set_network_profile(%{name: "x", signature_chain_id: "539"})
+    assert message =~ "hex"
+    assert {:error, _} = Signer.set_network_profile(%{name: "x", chain: 1})
+  end
+end
@@ -0,0 +1,178 @@
+defmodule Hyperliquid.SignerPolicyTest do
+  # The policy is process-wide inside the NIF, so these tests must not run
+  # alongside other signing tests.
+  use ExUnit.Case, async: false
+
+  alias Hyperliquid.Signer
+
+  @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+  @treasury "0x1234567890123456789012345678901234567890"
+  @stranger "0x0000000000000000000000000000000000000001"
+
+  @policy """
+  network = "mainnet"
+  allowed_actions = ["order", "usdSend", "withdraw3"]
+  allowed_destinations = ["#{@treasury}"]
+  max_transfer_amount = "1000"
+  max_order_notional = 50000
+  """
+
+  setup do
+    :ok = Signer.set_policy(@policy, "toml")
+    on_exit(fn -> Signer.clear_policy() end)
+  end
+
+  defp order_json(px, sz) do
+    Jason.encode!(%{
+      type: "order",
+      orders: [%{a: 0, b: true, p: px, s: sz, r: false, t: %{limit: %{tif: "Gtc"}}}],
+      grouping: "na"
+    })
+  end
+
+  defp violation({:error, {:policy_violation, details}}), do: details
+
+  test "signs requests that satisfy every rule" do
+    assert %{"r" => _, "s" => _} = Signer.sign_usd_send(@priv_key, @treasury, "999.5", 1, true)
+    assert %{"connection_id" => _} = Signer.sign_exchange_action_ex(@priv_key, order_json("100", "500"), 1, true, nil, nil)
+  end
+
+  test "rejects transfers to destinations outside the allowlist" do
+    assert %{"rule" => "allowed_destinations", "action" => "withdraw3"} =
+             violation(Signer.sign_withdraw3(@priv_key, @stranger, "1", 1, true))
+  end
+
+  test "rejects transfers above the per-transfer limit" do
+    assert %{"rule" => "max_transfer_amount", "action" => "usdSend"} =
+             violation(Signer.sign_usd_send(@priv_key, @treasury, "1000.01", 1, true))
+  end
+
+  test "compares amounts exactly" do
+    assert %{"rule" => "max_transfer_amount"} =
+             violation(Signer.sign_usd_send(@priv_key, @treasury, "1000.00000000000000000001", 1, true))
+
+    assert %{"r" => _} = Signer.sign_usd_send(@priv_key, @treasury, "1000.000000000000000000", 1, true)
+  end
+
+  test "limits sendAsset and usdClassTransfer like any other transfer" do
+    :ok = Signer.set_policy(~s({"allowed_destinations": ["#{@treasury}"], "max_transfer_amount": "1000"}), "json")
+
+    send_asset = %{
+      type: "sendAsset",
+      destination: @stranger,
+      sourceDex: "",
+      destinationDex: "spot",
+      token: "USDC",
+      amount: "1",
+      fromSubAccount: "",
+      nonce: 1
+    }
+
+    assert %{"rule" => "allowed_destinations", "action" => "sendAsset"} =
+             violation(Signer.sign_user_signed_action(@priv_key, send_asset, true))
+
+    assert %{"rule" => "max_transfer_amount", "action" => "usdClassTransfer"} =
+             violation(Signer.sign_usd_class_transfer(@priv_key, "1000.5", true, 1, true, @stranger))
+
+    assert %{"r" => _} = Signer.sign_usd_class_transfer(@priv_key, "1000", true, 1, true, nil)
+  end
+
+  test "limits the destination of sendToEvmWithData" do
+    :ok = Signer.set_policy(~s({"allowed_destinations": ["#{@treasury}"]}), "json")
+
+    send_to_evm = %{
+      type: "sendToEvmWithData",
+      token: "USDC",
+      amount: "1",
+      sourceDex: "",
+      destinationRecipient: @stranger,
+      addressEncoding: "hex",
+      destinationChainId: 42161,
+      gasLimit: 200_000,
+      data: "0x",
+      nonce: 1
+    }
+
+    assert %{"rule" => "allowed_destinations", "action" => "sendToEvmWithData"} =
+             violation(Signer.sign_user_signed_action(@priv_key, send_to_evm, true))
+
+    assert %{"rule" => "allowed_destinations", "action" => "sendToEvmWithData"} =
+             violation(Signer.sign_multi_sig_user_signed_payload(@priv_key, send_to_evm, @treasury, @treasury, true))
+  end
+
+  test "limits transfers within the account" do
+    :ok = Signer.set_policy(~s({"max_transfer_amount": "1000"}), "json")
+
+    vault_transfer = %{type: "vaultTransfer", vaultAddress: @treasury, isDeposit: true, usd: 1_000_000_001}
+
+    assert %{"rule" => "max_transfer_amount", "action" => "vaultTransfer", "message" => message} =
+             violation(Signer.sign_action(@priv_key, vault_transfer, 1, true, nil, nil))
+
+    assert message =~ "1000.000001"
+    assert %{"r" => _} = Signer.sign_action(@priv_key, %{vault_transfer | usd: 1_000_000_000}, 1, true, nil, nil)
+
+    sub_account_transfer = %{type: "subAccountTransfer", subAccountUser: @treasury, isDeposit: false, usd: 2_000_000_000}
+
+    assert %{"rule" => "max_transfer_amount", "action" => "subAccountTransfer"} =
+             violation(Signer.sign_action(@priv_key, sub_account_transfer, 1, true, nil, nil))
+
+    sub_account_spot_transfer = %{
+      type: "subAccountSpotTransfer",
+      subAccountUser: @treasury,
+      isDeposit: true,
+      token: "PURR:0xc4bf3f870c0e9465323c0b6ed28096c2",
+      amount: "1000.1"
+    }
+
+    assert %{"rule" => "max_transfer_amount", "action" => "subAccountSpotTransfer"} =
+             violation(Signer.sign_action(@priv_key, sub_account_spot_transfer, 1, true, nil, nil))
+
+    c_withdraw = %{type: "cWithdraw", wei: 100_000_000_001, nonce: 1}
+
+    assert %{"rule" => "max_transfer_amount", "action" => "cWithdraw"} =
+             violation(Signer.sign_user_signed_action(@priv_key, c_withdraw, true))
+  end
+
+  test "rejects TWAP orders under a notional limit" do
+    :ok = Signer.set_policy(~s({"max_order_notional": 50000}), "json")
+
+    twap = %{type: "twapOrder", twap: %{a: 1, b: true, s: "0.5", r: false, m: 30, t: false}}
+
+    assert %{"rule" => "max_order_notional", "action" => "twapOrder"} =
+             violation(Signer.sign_action(@priv_key, twap, 1, true, nil, nil))
+  end
+
+  test "rejects actions outside the allowlist" do
+    assert %{"rule" => "allowed_actions", "action" => "spotSend"} =
+             violation(Signer.sign_spot_send(@priv_key, @treasury, "PURR:0x1", "1", 1, true))
+  end
+
+  test "rejects orders above the notional limit" do
+    assert %{"rule" => "max_order_notional", "message" => message} =
+             violation(Signer.sign_exchange_action_ex(@priv_key, order_json("100", "600"), 1, true, nil, nil))
+
+    assert message =~ "60000"
+  end
+
+  test "rejects the wrong network" do
+    assert %{"rule" => "network"} = violation(Signer.sign_usd_send(@priv_key, @treasury, "1", 1, false))
+  end
+
+  test "rejects opaque signing unless allowed" do
+    cid = "0x" <> String.duplicate("ab", 32)
+    assert %{"rule" => "allow_opaque_signing"} = violation(Signer.sign_l1_action(@priv_key, cid, true))
+
+    :ok = Signer.set_policy(~s({"allow_opaque_signing": true}), "json")
+    assert %{"connection_id" => ^cid} = Signer.sign_l1_action(@priv_key, cid, true)
+  end
+
+  test "clearing the policy allows everything again" do
+    Signer.clear_policy()
+    assert %{"r" => _} = Signer.sign_usd_send(@priv_key, @stranger, "1000000", 1, false)
+  end
+
+  test "rejects malformed policies" do
+    assert {:error, "policy error: " <> _} = Signer.set_policy(~s({"max_notional": 1}), "json")
+    assert {:error, "policy error: " <> _} = Signer.set_policy("", "yaml")
+  end
+end
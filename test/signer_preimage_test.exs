@@ -0,0 +1,41 @@
+defmodule Hyperliquid.SignerPreimageTest do
+  use ExUnit.Case, async: true
+
+  alias Hyperliquid.Signer
+
+  @nonce 1_234_567_890
+  @vault "0x1234567890123456789012345678901234567890"
+
+  test "segments concatenate to the preimage of the connection id" do
+    action_json = ~s({"type":"order","orders":[{"a":1,"b":true,"p":"1.5","s":"2","r":false,"t":{"limit":{"tif":"Gtc"}}}],"grouping":"na"})
+
+    explained = Signer.explain_connection_id(action_json, @nonce, @vault, 1_700_000_000_000)
+
+    assert Enum.map(explained["segments"], & &1["name"]) == ["action", "nonce", "vault", "expires_after"]
+    assert Enum.at(explained["segments"], 1)["hex"] == "0x00000000499602d2"
+    assert Enum.at(explained["segments"], 2)["hex"] == "0x01" <> String.trim_leading(@vault, "0x")
+    assert Enum.at(explained["segments"], 3)["hex"] == "0x000000018bcfe56800"
+
+    concatenated = "0x" <> Enum.map_join(explained["segments"], &String.trim_leading(&1["hex"], "0x"))
+    assert concatenated == explained["preimage"]
+    assert explained["action_json"] == action_json
+    assert explained["connection_id"] == Signer.compute_connection_id_ex(action_json, @nonce, @vault, 1_700_000_000_000)
+  end
+
+  test "without a vault or expiresAfter" do
+    explained = Signer.explain_connection_id(~s({"type":"noop"}), 1, nil, nil)
+
+    assert explained["segments"] == [
+             %{"name" => "action", "hex" => "0x81a474797065a46e6f6f70"},
+             %{"name" => "nonce", "hex" => "0x0000000000000001"},
+             %{"name" => "vault", "hex" => "0x00"}
+           ]
+  end
+
+  test "decode_action_msgpack keeps key order" do
+    %{"msgpack" => bytes, "json" => json} = Signer.encode_action(%{type: "twapCancel", t: 7, a: 1})
+    assert Signer.decode_action_msgpack(bytes) == json
+    assert Signer.decode_action_msgpack(bytes) == ~s({"type":"twapCancel","a":1,"t":7})
+    assert {:error, _} = Signer.decode_action_msgpack(<<0xC1>>)
+  end
+end
@@ -0,0 +1,76 @@
+defmodule Hyperliquid.SignerRecoverTest do
+  use ExUnit.Case, async: true
+
+  alias Hyperliquid.Signer
+
+  @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+  @address "0xE5cA49Fb3bD9A581F0D1EF9CB5D7177Da08bf901"
+  @other "0x1234567890123456789012345678901234567890"
+
+  defp rsv(%{"r" => r, "s" => s, "v" => v}), do: [r, s, v]
+
+  test "usdSend" do
+    sig = rsv(Signer.sign_usd_send(@priv_key, @other, "1000", 1, true))
+
+    assert apply(Signer, :recover_usd_send, [@other, "1000", 1, true] ++ sig) == @address
+    assert apply(Signer, :verify_usd_send, [@address, @other, "1000", 1, true] ++ sig)
+    refute apply(Signer, :verify_usd_send, [@address, @other, "1001", 1, true] ++ sig)
+    refute apply(Signer, :verify_usd_send, [@other, @other, "1000", 1, true] ++ sig)
+  end
+
+  test "withdraw3" do
+    sig = rsv(Signer.sign_withdraw3(@priv_key, @other, "5", 2, false))
+
+    assert apply(Signer, :recover_withdraw3, [@other, "5", 2, false] ++ sig) == @address
+    assert apply(Signer, :verify_withdraw3, [@address, @other, "5", 2, false] ++ sig)
+    refute apply(Signer, :verify_withdraw3, [@address, @other, "5", 2, true] ++ sig)
+  end
+
+  test "spotSend" do
+    sig = rsv(Signer.sign_spot_send(@priv_key, @other, "PURR:0x1", "3", 3, true))
+
+    assert apply(Signer, :recover_spot_send, [@other, "PURR:0x1", "3", 3, true] ++ sig) == @address
+    assert apply(Signer, :verify_spot_send, [@address, @other, "PURR:0x1", "3", 3, true] ++ sig)
+    refute apply(Signer, :verify_spot_send, [@address, @other, "HYPE:0x2", "3", 3, true] ++ sig)
+  end
+
+  test "approveBuilderFee" do
+    sig = rsv(Signer.sign_approve_builder_fee(@priv_key, @other, "0.001%", 4, true))
+
+    assert apply(Signer, :recover_approve_builder_fee, [@other, "0.001%", 4, true] ++ sig) == @address
+    assert apply(Signer, :verify_approve_builder_fee, [@address, @other, "0.001%", 4, true] ++ sig)
+  end
+
+  test "approveAgent" do
+    sig = rsv(Signer.sign_approve_agent(@priv_key, @other, "bot", 5, true))
+
+    assert apply(Signer, :recover_approve_agent, [@other, "bot", 5, true] ++ sig) == @address
+    assert apply(Signer, :verify_approve_agent, [@address, @other, "bot", 5, true] ++ sig)
+    refute apply(Signer, :verify_approve_agent, [@address, @other, nil, 5, true] ++ sig)
+  end
+
+  test "multiSig" do
+    action_json = Jason.encode!(%{signatureChainId: "0x66eee", payload: %{action: %{type: "noop"}}})
+    sig = rsv(Signer.sign_multi_sig_action_ex(@priv_key, action_json, 6, true, nil, nil))
+
+    assert apply(Signer, :recover_multi_sig_action, [action_json, 6, true, nil, nil] ++ sig) == @address
+    assert apply(Signer, :verify_multi_sig_action, [@address, action_json, 6, true, nil, nil] ++ sig)
+    refute apply(Signer, :verify_multi_sig_action, [@address, action_json, 6, true, @other, nil] ++ sig)
+  end
+
+  test "generic typed data" do
+    domain = Jason.encode!(%{name: "Test", version: "1", chainId: 1, verifyingContract: "0x0000000000000000000000000000000000000000"})
+    types = Jason.encode!(%{"Mail" => [%{name: "contents", type: "string"}]})
+    message = Jason.encode!(%{contents: "hello"})
+    sig = rsv(Signer.sign_typed_data(@priv_key, domain, types, message, "Mail"))
+
+    assert apply(Signer, :recover_typed_data, [domain, types, message, "Mail"] ++ sig) == @address
+    assert apply(Signer, :verify_typed_data, [@address, domain, types, message, "Mail"] ++ sig)
+    refute apply(Signer, :verify_typed_data, [@address, domain, types, Jason.encode!(%{contents: "bye"}), "Mail"] ++ sig)
+  end
+
+  test "malformed arguments are errors, not false" do
+    assert {:error, "parse error: invalid v: 3"} = Signer.verify_usd_send(@address, @other, "1", 1, true, "0x1", "0x1", 3)
+    assert {:error, "parse error: invalid address: " <> _} = Signer.verify_usd_send("0xnope", @other, "1", 1, true, "0x1", "0x1", 27)
+  end
+end
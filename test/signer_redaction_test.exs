@@ -0,0 +1,63 @@
+defmodule Hyperliquid.SignerRedactionTest do
+  use ExUnit.Case, async: true
+
+  alias Hyperliquid.Signer
+
+  @key_hex "822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+  @addr "0x1234567890123456789012345678901234567890"
+  @action_json ~S({"type":"scheduleCancel","time":1234567890})
+  @ms_action_json ~S({"signatureChainId":"0x66eee","signatures":[],"payload":{}})
+
+  # Malformed variants of a real key: every one must be rejected without echoing any of it.
+  @bad_keys [
+    "0x" <> @key_hex <> "zz",
+    "0x" <> String.slice(@key_hex, 0, 40),
+    "0xzz" <> String.slice(@key_hex, 2, 62),
+    @key_hex <> @key_hex,
+    "0x" <> String.duplicate("0", 64),
+    "0x" <> String.duplicate("f", 64)
+  ]
+
+  defp signing_calls(key) do
+    [
+      fn -> Signer.load_wallet(key) end,
+      fn -> Signer.derive_address(key) end,
+      fn -> Signer.sign_exchange_action(key, @action_json, 1, true, nil) end,
+      fn -> Signer.sign_exchange_action_ex(key, @action_json, 1, true, nil, nil) end,
+      fn -> Signer.sign_l1_action(key, "0x" <> String.duplicate("0", 64), true) end,
+      fn -> Signer.sign_multi_sig_action_ex(key, @ms_action_json, 1, true, nil, nil) end,
+      fn -> Signer.sign_typed_data(key, "{}", "{}", "{}", "Foo") end,
+      fn -> Signer.sign_usd_send(key, @addr, "1", 1, true) end,
+      fn -> Signer.sign_withdraw3(key, @addr, "1", 1, true) end,
+      fn -> Signer.sign_spot_send(key, @addr, "USDC", "1", 1, true) end,
+      fn -> Signer.sign_approve_builder_fee(key, @addr, "0.001%", 1, true) end,
+      fn -> Signer.sign_approve_agent(key, @addr, nil, 1, true) end,
+      fn -> Signer.generate_agent(key, nil, 1, true) end,
+      fn -> Signer.encrypt_keystore(key, System.tmp_dir!(), "pw", nil) end
+    ]
+  end
+
+  # Any 8-hex-char window of the key appearing in the error would be a leak.
+  defp assert_redacted(error, key) do
+    rendered = inspect(error, limit: :infinity, printable_limit: :infinity)
+    hex = key |> String.trim_leading("0x") |> String.downcase()
+
+    for i <- 0..(String.length(hex) - 8)//4 do
+      refute rendered =~ String.slice(hex, i, 8), "error leaked key material: #{rendered}"
+    end
+  end
+
+  test "malformed private keys never appear in error terms" do
+    for key <- @bad_keys, call <- signing_calls(key) do
+      assert {:error, "wallet error: " <> _} = error = call.()
+      assert_redacted(error, key)
+    end
+  end
+
+  test "invalid mnemonic words are not echoed back" do
+    phrase = "legal winner thank year wave sausage worth useful legal winner thank yellowx"
+
+    assert {:error, error} = Signer.derive_mnemonic_address(phrase, nil, nil)
+    refute error =~ "yellowx"
+  end
+end
@@ -0,0 +1,85 @@
+defmodule Hyperliquid.SignerTicksTest do
+  # The szDecimals table is process-wide inside the NIF, so these tests must not run
+  # alongside other signing tests.
+  use ExUnit.Case, async: false
+
+  alias Hyperliquid.Signer
+  alias Hyperliquid.Utils.Format
+
+  @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+
+  defp order_json(asset, px, sz) do
+    Jason.encode!(%{
+      type: "order",
+      orders: [%{a: asset, b: true, p: px, s: sz, r: false, t: %{limit: %{tif: "Gtc"}}}],
+      grouping: "na"
+    })
+  end
+
+  describe "format_price / format_size" do
+    test "truncate cuts down to the tick and lot size" do
+      assert Signer.format_price("50000.123456", 5, false, :truncate) == "50000"
+      assert Signer.format_price("123456.7", 0, false, :truncate) == "123456"
+      assert Signer.format_price("0.0000123456789", 0, true, :truncate) == "0.00001234"
+      assert Signer.format_price("0.0000123456789", 0, false, :truncate) == "0.000012"
+      assert Signer.format_size("1.23456789", 5, :truncate) == "1.23456"
+      assert Signer.format_size("0.0001", 3, :truncate) == "0"
+    end
+
+    test "validate leaves fitting values alone and rejects the rest" do
+      assert Signer.format_price("1.2345", 0, false, :validate) == "1.2345"
+      assert Signer.format_price("30000.0", 5, false, :validate) == "30000"
+      assert {:error, message} = Signer.format_price("1.23456", 0, false, :validate)
+      assert message =~ "more than 5 significant figures"
+      assert {:error, message} = Signer.format_price("0.00123", 4, false, :validate)
+      assert message =~ "more than 2 decimals"
+      assert {:error, _} = Signer.format_size("0.0011", 3, :validate)
+      assert {:error, _} = Signer.format_price("-1", 0, false, :validate)
+      assert {:error, _} = Signer.format_price("1", 0, false, :nearest)
+    end
+
+    test "Utils.Format delegates to the signer" do
+      assert Format.format_price(50_000.123456, 5) == "50000"
+      assert Format.format_price("0.0000123456789", 0, perp: false) == "0.00001234"
+      assert Format.format_size(0.001, 3) == "0.001"
+      assert Format.validate_price("1.2345", 0) == {:ok, "1.2345"}
+      assert {:error, _} = Format.validate_size("1.5", 0)
+      assert Format.format_size("0.0001", 3) == "0"
+      assert Format.format_price(0, 5) == "0"
+      assert_raise ArgumentError, fn -> Format.format_size("0.0001", 3, nonzero: true) end
+      assert_raise ArgumentError, fn -> Format.format_price("-1", 5) end
+    end
+  end
+
+  describe "enforcement" do
+    setup do
+      on_exit(fn -> Signer.clear_sz_decimals() end)
+    end
+
+    test "rejects orders that break the tick or lot size" do
+      :ok = Signer.set_sz_decimals(%{0 => 5, 10_000 => 2}, true)
+
+      assert %{"connection_id" => _} = Signer.sign_exchange_action_ex(@priv_key, order_json(0, "30000", "0.1"), 1, true, nil, nil)
+      assert %{"connection_id" => _} = Signer.sign_exchange_action_ex(@priv_key, order_json(10_000, "0.12345", "1.5"), 1, true, nil, nil)
+
+      assert {:error, message} = Signer.sign_exchange_action_ex(@priv_key, order_json(0, "30000.5", "0.1"), 1, true, nil, nil)
+      assert message =~ "order 0: price \"30000.5\""
+      assert {:error, message} = Signer.sign_exchange_action_ex(@priv_key, order_json(0, "30000", "0.000001"), 1, true, nil, nil)
+      assert message =~ "more than 5 decimals"
+
+      modify = Jason.encode!(%{type: "batchModify", modifies: [%{oid: 1, order: %{a: 10_000, b: true, p: "1", s: "1.234", r: false, t: %{limit: %{tif: "Gtc"}}}}]})
+      assert {:error, _} = Signer.sign_exchange_action_ex(@priv_key, modify, 1, true, nil, nil)
+    end
+
+    test "fails closed for assets missing from the table" do
+      :ok = Signer.set_sz_decimals(%{0 => 5}, true)
+      assert {:error, message} = Signer.sign_exchange_action_ex(@priv_key, order_json(1, "3000", "1"), 1, true, nil, nil)
+      assert message =~ "no szDecimals known for asset 1"
+    end
+
+    test "a table pushed without enforce only informs" do
+      :ok = Signer.set_sz_decimals(%{0 => 5}, false)
+      assert %{"connection_id" => _} = Signer.sign_exchange_action_ex(@priv_key, order_json(0, "30000.5", "0.1"), 1, true, nil, nil)
+    end
+  end
+end
@@ -20,4 +20,97 @@ defmodule Hyperliquid.SignerUserSignedTest do
       assert sig["v"] == 28
     end
   end
+
+  describe "generate_agent" do
+    test "generates a new agent and signs approveAgent for it with the master key" do
+      nonce = 1_234_567_890
+
+      agent = Signer.generate_agent(@priv_key, "bot", nonce, true)
+
+      assert Signer.derive_address(agent["agent_private_key"]) == agent["agent_address"]
+      assert Signer.derive_address(agent["agent_wallet"]) == agent["agent_address"]
+
+      assert Jason.decode!(agent["action_json"]) == %{
+               "type" => "approveAgent",
+               "hyperliquidChain" => "Mainnet",
+               "signatureChainId" => "0xa4b1",
+               "agentAddress" => agent["agent_address"],
+               "agentName" => "bot",
+               "nonce" => nonce
+             }
+
+      assert agent["signature"] ==
+               Signer.sign_approve_agent(@priv_key, agent["agent_address"], "bot", nonce, true)
+    end
+
+    test "omits agentName when none is given and never reuses keys" do
+      a = Signer.generate_agent(@priv_key, nil, 1, false)
+      b = Signer.generate_agent(@priv_key, nil, 1, false)
+
+      refute Map.has_key?(Jason.decode!(a["action_json"]), "agentName")
+      assert a["agent_private_key"] != b["agent_private_key"]
+    end
+  end
+
+  describe "sign_usd_class_transfer" do
+    test "signs the user-signed struct and returns the action" do
+      sig = Signer.sign_usd_class_transfer(@priv_key, "100", true, 1_234_567_890, true, nil)
+
+      assert Jason.decode!(sig["action_json"]) == %{
+               "type" => "usdClassTransfer",
+               "signatureChainId" => "0xa4b1",
+               "hyperliquidChain" => "Mainnet",
+               "amount" => "100",
+               "toPerp" => true,
+               "nonce" => 1_234_567_890
+             }
+
+      action = %{type: "usdClassTransfer", amount: "100", toPerp: true, nonce: 1_234_567_890}
+      assert Map.take(Signer.sign_user_signed_action(@priv_key, action, true), ["r", "s", "v"]) == Map.take(sig, ["r", "s", "v"])
+    end
+
+    test "appends the sub-account to the amount" do
+      sub_account = "0x1234567890123456789012345678901234567890"
+      sig = Signer.sign_usd_class_transfer(@priv_key, "5.5", false, 1, false, sub_account)
+
+      assert Jason.decode!(sig["action_json"])["amount"] == "5.5 subaccount:#{sub_account}"
+      assert {:error, "invalid sub_account: " <> _} = Signer.sign_usd_class_transfer(@priv_key, "5.5", false, 1, false, "0x12")
+    end
+  end
+
+  describe "sign_user_signed_action" do
+    @address "0x1234567890123456789012345678901234567890"
+
+    test "signs the same digest as the dedicated NIFs" do
+      sig = Signer.sign_user_signed_action(@priv_key, %{type: "usdSend", destination: @address, amount: "1000", time: 1_234_567_890}, true)
+      assert Map.take(sig, ["r", "s", "v"]) == Map.take(Signer.sign_usd_send(@priv_key, @address, "1000", 1_234_567_890, true), ["r", "s", "v"])
+
+      action = [type: "approveBuilderFee", builder: @address, maxFeeRate: "0.01%", nonce: 7]
+      assert Map.take(Signer.sign_user_signed_action(@priv_key, action, false), ["r", "s", "v"]) ==
+               Map.take(Signer.sign_approve_builder_fee(@priv_key, @address, "0.01%", 7, false), ["r", "s", "v"])
+    end
+
+    test "matches the generic typed-data signer" do
+      domain = %{name: "HyperliquidSignTransaction", version: "1", chainId: 42_161, verifyingContract: "0x0000000000000000000000000000000000000000"}
+      types = %{"HyperliquidTransaction:CDeposit" => [%{name: "hyperliquidChain", type: "string"}, %{name: "wei", type: "uint64"}, %{name: "nonce", type: "uint64"}]}
+      message = %{hyperliquidChain: "Mainnet", wei: 100_000_000, nonce: 1}
+
+      expected = Signer.sign_typed_data(@priv_key, Jason.encode!(domain), Jason.encode!(types), Jason.encode!(message), "HyperliquidTransaction:CDeposit")
+      sig = Signer.sign_user_signed_action(@priv_key, %{type: "cDeposit", wei: 100_000_000, nonce: 1}, true)
+
+      assert Map.take(sig, ["r", "s", "v"]) == Map.take(expected, ["r", "s", "v"])
+      assert sig["action_json"] == ~s({"type":"cDeposit","signatureChainId":"0xa4b1","hyperliquidChain":"Mainnet","wei":100000000,"nonce":1})
+    end
+
+    test "rejects L1 actions, unknown fields and the wrong network" do
+      assert {:error, "parse error: cancel is not a user-signed action"} =
+               Signer.sign_user_signed_action(@priv_key, %{type: "cancel", cancels: []}, true)
+
+      assert {:error, message} = Signer.sign_user_signed_action(@priv_key, %{type: "cDeposit", wei: 1, nonce: 1, extra: 1}, true)
+      assert message =~ "unknown field `extra`"
+
+      assert {:error, message} = Signer.sign_user_signed_action(@priv_key, %{type: "cDeposit", hyperliquidChain: "Testnet", wei: 1, nonce: 1}, true)
+      assert message =~ "does not match the Mainnet network"
+    end
+  end
 end
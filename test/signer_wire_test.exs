@@ -0,0 +1,57 @@
+defmodule Hyperliquid.SignerWireTest do
+  use ExUnit.Case, async: true
+
+  alias Hyperliquid.{Signer, Utils}
+
+  @priv_key "0x822e9959e022b78423eb653a62ea0020cd283e71a2a8133a6ff2aeffaf373cff"
+
+  test "float_to_wire matches the reference SDK" do
+    assert Signer.float_to_wire(0.1 + 0.2) == "0.3"
+    assert Signer.float_to_wire(1.0e-5) == "0.00001"
+    assert Signer.float_to_wire(50_000.0) == "50000"
+    assert Signer.float_to_wire(-0.0) == "0"
+    assert Signer.float_to_wire(123_456.12345678) == "123456.12345678"
+    assert {:error, message} = Signer.float_to_wire(0.123456789)
+    assert message =~ "cannot be represented with 8 decimals"
+  end
+
+  test "decimal_to_wire normalizes exactly" do
+    assert Signer.decimal_to_wire("100.500") == "100.5"
+    assert Signer.decimal_to_wire("1.0e-5") == "0.00001"
+    assert Signer.decimal_to_wire("-0.000") == "0"
+    assert Signer.decimal_to_wire("0.30000000000000004") == "0.3"
+    assert Signer.decimal_to_wire("123456789012.12345678") == "123456789012.12345678"
+    assert {:error, _} = Signer.decimal_to_wire("0.123456789")
+    assert {:error, _} = Signer.decimal_to_wire("abc")
+  end
+
+  test "order prices and sizes are normalized before hashing" do
+    order = fn p, s ->
+      Jason.encode!(%{type: "order", orders: [%{a: 0, b: true, p: p, s: s, r: false, t: %{limit: %{tif: "Gtc"}}}], grouping: "na"})
+    end
+
+    expected = Signer.sign_exchange_action_ex(@priv_key, order.("30000", "0.1"), 1, true, nil, nil)
+
+    assert Signer.sign_exchange_action_ex(@priv_key, order.("30000.0", "0.10"), 1, true, nil, nil) == expected
+    assert Signer.sign_exchange_action_ex(@priv_key, order.(30_000, 0.1), 1, true, nil, nil) == expected
+    assert {:error, _} = Signer.sign_exchange_action_ex(@priv_key, order.("30000", "0.123456789"), 1, true, nil, nil)
+  end
+
+  test "the JSON signing path returns the normalized action it signed" do
+    order = ~s({"type":"order","orders":[{"a":0,"b":true,"p":"30000.0","s":"0.10","r":false,"t":{"limit":{"tif":"Gtc"}}}],"grouping":"na"})
+    twap = ~s({"type":"twapOrder","twap":{"a":1,"b":true,"s":"0.50","r":false,"m":30,"t":false}})
+
+    assert %{"action_json" => order_json} = Signer.sign_exchange_action(@priv_key, order, 1, true, nil)
+    assert order_json =~ ~s("p":"30000","s":"0.1")
+
+    assert %{"action_json" => twap_json} = Signer.sign_exchange_action_ex(@priv_key, twap, 1, true, nil, nil)
+    assert twap_json == ~s({"type":"twapOrder","twap":{"a":1,"b":true,"s":"0.5","r":false,"m":30,"t":false}})
+  end
+
+  test "Utils.to_wire formats or raises" do
+    assert Utils.to_wire(0.1 + 0.2) == "0.3"
+    assert Utils.to_wire(5) == "5"
+    assert Utils.to_wire("51000.0") == "51000"
+    assert_raise ArgumentError, fn -> Utils.to_wire(1.0e-9) end
+  end
+end